@@ -0,0 +1,62 @@
+//! Measures writes to an `AtomicCell` holding a heap-allocated value, with and
+//! without concurrent readers. Run with `cargo bench --bench swap`.
+
+#[cfg(not(loom))]
+fn main() {
+    use ::std::sync::atomic::{AtomicBool, Ordering};
+    use ::std::sync::Arc;
+    use ::std::time::Instant;
+
+    const WRITES: u64 = 1_000_000;
+
+    for &readers in &[0, 1, 3] {
+        let cell = Arc::new(::impatience::AtomicCell::new([0u64; 2]));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let thandles = (0..readers)
+            .map(|_| {
+                let cell = cell.clone();
+                let done = done.clone();
+
+                ::std::thread::spawn(move || {
+                    while !done.load(Ordering::Relaxed) {
+                        ::std::hint::black_box(cell.get());
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let start = Instant::now();
+
+        for i in 0..WRITES {
+            cell.set([i; 2]);
+        }
+
+        let set = start.elapsed();
+        let start = Instant::now();
+
+        for i in 0..WRITES {
+            let (version, _) = cell.get_versioned();
+            let _ =
+                ::std::hint::black_box(cell.set_if_version(version, [i; 2]));
+        }
+
+        let set_if_version = start.elapsed();
+
+        done.store(true, Ordering::Relaxed);
+
+        for thandle in thandles {
+            thandle.join().unwrap();
+        }
+
+        println!(
+            "{} readers: set {:.1} ns, get_versioned + set_if_version {:.1} ns",
+            readers,
+            set.as_nanos() as f64 / WRITES as f64,
+            set_if_version.as_nanos() as f64 / WRITES as f64,
+        );
+    }
+}
+
+#[cfg(loom)]
+fn main() {}
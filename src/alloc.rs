@@ -7,19 +7,13 @@ pub struct Hole {
 }
 
 #[repr(align(128))]
-pub union Slot<T>
-where
-    T: std::Copy,
-{
+pub union Slot<T> {
     uninit: (),
     hole: Hole,
     element: std::ManuallyDrop<T>,
 }
 
-impl<T> Slot<T>
-where
-    T: std::Copy,
-{
+impl<T> Slot<T> {
     pub fn uninit() -> Self {
         Self { uninit: () }
     }
@@ -43,18 +37,12 @@ where
     }
 }
 
-pub struct Layout<T>
-where
-    T: std::Copy,
-{
+pub struct Layout<T> {
     inner: std::Layout,
     phantom: std::PhantomData<T>,
 }
 
-impl<T> Layout<T>
-where
-    T: std::Copy,
-{
+impl<T> Layout<T> {
     pub fn array(n: usize) -> Self {
         const MIN_SIZE: usize = 128;
         const MAX_SIZE: usize = (usize::MAX >> 1) + 1;
@@ -83,17 +71,11 @@ where
     }
 }
 
-pub struct Allocator<T>
-where
-    T: std::Copy,
-{
+pub struct Allocator<T> {
     memory_block: *mut Slot<T>,
 }
 
-impl<T> Allocator<T>
-where
-    T: std::Copy,
-{
+impl<T> Allocator<T> {
     pub fn new(layout: Layout<T>) -> Self {
         Self {
             memory_block: unsafe { std::alloc(layout.inner) } as *mut _,
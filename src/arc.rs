@@ -1,55 +1,619 @@
 use crate::std;
 
-#[repr(align(128))]
-struct ArcInner<T> {
-    count: crate::Align128<std::AtomicIsize>,
+// The fields are laid out in order, so the offset of the data within an
+// `ArcInner<[T]>` can be computed. See: `slice_layout`
+#[repr(C, align(128))]
+struct ArcInner<T>
+where
+    T: ?std::Sized,
+{
+    count: crate::Align128<Counts>,
+    pool: *const crate::Pool,
+    version: u64,
+    /// The layout of the allocation, which is freed after the data has been
+    /// dropped or moved out, i.e. it can't be computed from the data.
+    layout: std::Layout,
+    /// `Tag::of::<T>()`, while the `ArcInner` is allocated. See:
+    /// `RawArc::check`
+    #[cfg(debug_assertions)]
+    tag: Tag,
     data: T,
 }
 
-pub struct Arc<T> {
+/// The reference counts of an `ArcInner`, which share a cache line.
+struct Counts {
+    strong: std::AtomicIsize,
+    /// The number of `Weak`s plus 1, as long as the strong count hasn't
+    /// reached 0. The memory is freed, when the weak count reaches 0.
+    weak: std::AtomicIsize,
+}
+
+impl Counts {
+    fn new(strong: isize) -> Self {
+        Self {
+            strong: std::AtomicIsize::new(strong),
+            weak: std::AtomicIsize::new(1),
+        }
+    }
+}
+
+/// Marks allocations of `ArcInner`s in debug builds.
+#[cfg(debug_assertions)]
+const MAGIC: u64 = 0x6172_635f_696e_6e72;
+
+/// Marks an allocation of an `ArcInner<T>` in debug builds, so pointers to
+/// allocations of other types are caught, too.
+#[cfg(debug_assertions)]
+#[derive(Clone, Copy, PartialEq)]
+struct Tag {
+    magic: u64,
+    type_name: &'static str,
+}
+
+#[cfg(debug_assertions)]
+impl Tag {
+    fn of<T>() -> Self
+    where
+        T: ?std::Sized,
+    {
+        Self {
+            magic: MAGIC,
+            type_name: std::type_name::<T>(),
+        }
+    }
+}
+
+/// The initial value of the reference count of an `ArcInner` allocated by
+/// `Arc::raw`. It keeps the count far away from 0, until the count has been
+/// initialized, so references can be acquired and released before that.
+const COUNT_BIAS: isize = isize::MAX / 2;
+
+/// The weak count of an `ArcInner`, while `Arc::get_mut` checks, whether an
+/// `Arc` is unique. It keeps other threads from creating a `Weak` meanwhile.
+const WEAK_LOCKED: isize = -1;
+
+/// A thread-safe reference-counting pointer, whose reference count is kept on
+/// a cache line of its own.
+///
+/// Unlike `std::sync::Arc`, the allocation is aligned to 128 bytes, i.e. the
+/// reference count and the data never share a cache line with other
+/// allocations. Cloning and dropping the pointer doesn't slow down threads,
+/// which only read the data or write other memory.
+///
+/// # Examples
+///
+/// ```
+/// let arc = impatience::Arc::new(5u32);
+/// let clone = arc.clone();
+///
+/// assert_eq!(*clone, 5);
+/// assert_eq!(impatience::Arc::strong_count(&arc), 2);
+/// assert!(impatience::Arc::ptr_eq(&arc, &clone));
+/// ```
+///
+/// Slices and strings are copied into a single allocation:
+///
+/// ```
+/// let symbols: impatience::Arc<[u32]> = (0..4).collect();
+/// let name = impatience::Arc::<str>::from("impatience");
+///
+/// assert_eq!(&*symbols, &[0, 1, 2, 3]);
+/// assert_eq!(&*name, "impatience");
+/// ```
+pub struct Arc<T>
+where
+    T: ?std::Sized,
+{
     inner: std::NonNull<ArcInner<T>>,
     phantom: std::PhantomData<ArcInner<T>>,
 }
 
+// SAFETY: Every `Arc` shares the data with all of its clones, which may drop
+//   it on any thread.
+unsafe impl<T> std::Send for Arc<T> where T: ?std::Sized + std::Send + std::Sync {}
+unsafe impl<T> std::Sync for Arc<T> where T: ?std::Sized + std::Send + std::Sync {}
+
+impl<T> Arc<T>
+where
+    T: ?std::Sized,
+{
+    /// Returns the number of `Arc`s pointing to the same allocation.
+    ///
+    /// Other threads may clone or drop them at any time, i.e. the result may
+    /// be outdated right away.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().count.strong.load(std::SeqCst) as usize
+    }
+
+    /// Returns `true`, if both `Arc`s point to the same allocation, not just
+    /// to equal data.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        // The length of slices is implied by the allocation.
+        this.inner.as_ptr() as *const u8 == other.inner.as_ptr() as *const u8
+    }
+
+    /// Creates a `Weak` pointing to the same allocation.
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        let weak = &this.inner().count.weak;
+        let mut count = weak.load(std::Relaxed);
+
+        loop {
+            if count == WEAK_LOCKED {
+                std::yield_now();
+                count = weak.load(std::Relaxed);
+                continue;
+            }
+
+            // Synchronizes with the unlock of `is_unique`.
+            match weak.compare_exchange_weak(
+                count,
+                count + 1,
+                std::Acquire,
+                std::Relaxed,
+            ) {
+                std::Ok(_) => {
+                    break;
+                }
+                std::Err(count_current) => {
+                    count = count_current;
+                }
+            }
+        }
+
+        Weak {
+            inner: this.inner,
+            phantom: std::PhantomData,
+        }
+    }
+
+    /// Returns the number of `Weak`s pointing to the same allocation.
+    pub fn weak_count(this: &Self) -> usize {
+        match this.inner().count.weak.load(std::SeqCst) {
+            // Only unique `Arc`s are checked for other `Weak`s.
+            WEAK_LOCKED => 0,
+            count => count as usize - 1,
+        }
+    }
+
+    /// Mutably borrows the data, if no other `Arc` or `Weak` points to the
+    /// same allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut arc = impatience::Arc::new(5u32);
+    /// let clone = arc.clone();
+    ///
+    /// assert!(impatience::Arc::get_mut(&mut arc).is_none());
+    ///
+    /// drop(clone);
+    /// *impatience::Arc::get_mut(&mut arc).unwrap() += 1;
+    ///
+    /// assert_eq!(*arc, 6);
+    /// ```
+    pub fn get_mut(this: &mut Self) -> std::Option<&mut T> {
+        if Self::is_unique(this) {
+            // SAFETY: No one else can access the data, while `this` is
+            //   borrowed mutably.
+            std::Some(unsafe { &mut (*this.inner.as_ptr()).data })
+        } else {
+            std::None
+        }
+    }
+
+    /// Returns `true`, if no other `Arc` or `Weak` points to the same
+    /// allocation.
+    ///
+    /// Locking the weak count rules out that another `Arc` creates a `Weak`
+    /// and is dropped in between checking both counts. Acquiring both counts
+    /// synchronizes with the accesses of dropped `Arc`s and `Weak`s.
+    fn is_unique(this: &mut Self) -> bool {
+        let count = &this.inner().count;
+
+        if count
+            .weak
+            .compare_exchange(1, WEAK_LOCKED, std::Acquire, std::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+
+        let unique = count.strong.load(std::Acquire) == 1;
+
+        count.weak.store(1, std::Release);
+
+        unique
+    }
+
+    fn inner(&self) -> &ArcInner<T> {
+        // SAFETY: The memory isn't freed, while this reference is alive.
+        unsafe { self.inner.as_ref() }
+    }
+
+    /// Decrements the weak count of an `ArcInner` and frees it, if the count
+    /// reaches 0.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a weak reference, which is released.
+    unsafe fn release_weak(inner: *mut ArcInner<T>) {
+        if 1 == (*inner).count.0.weak.fetch_sub(1, std::Release) {
+            // See: `Drop::drop` for `Arc`
+            std::fence(std::Acquire);
+
+            Self::free(inner);
+        }
+    }
+
+    /// Frees the memory of an `ArcInner`, whose data has already been dropped
+    /// or moved out, or returns it to its pool.
+    unsafe fn free(inner: *mut ArcInner<T>) {
+        let pool = (*inner).pool;
+
+        #[cfg(debug_assertions)]
+        {
+            (*inner).tag.magic = 0;
+        }
+
+        if pool.is_null() {
+            std::dealloc(inner as _, (*inner).layout);
+        } else {
+            (*pool).dealloc(inner as _);
+        }
+    }
+
+    /// Frees the memory of an `ArcInner`, whose reference count hasn't been
+    /// initialized, after its data has been moved out or hasn't been
+    /// initialized at all.
+    ///
+    /// Readers, which found a block through the handle of a cell before it has
+    /// been recycled, may still hold a reference for a moment, until they have
+    /// noticed, that the handle points elsewhere. See:
+    /// `ArcHandle::acquire_counted`. The count must reach 0 before the block is
+    /// returned to its pool. Otherwise, those readers could acquire a reference
+    /// to the next use of the block, which `Arc::raw_uninit_in` erases when it
+    /// resets the count.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own the `ArcInner` and no other thread may release the
+    /// biased count. The pointer must not be used afterwards.
+    unsafe fn free_unshared(inner: *mut ArcInner<T>) {
+        let strong = &(*inner).count.0.strong;
+
+        while strong
+            .compare_exchange_weak(COUNT_BIAS, 0, std::SeqCst, std::SeqCst)
+            .is_err()
+        {
+            std::yield_now();
+        }
+
+        Self::free(inner);
+    }
+}
+
 impl<T> Arc<T> {
-    pub fn raw(data: T) -> *const () {
-        let uninit = unsafe {
-            std::alloc(std::Layout::new::<ArcInner<T>>()) as *mut ArcInner<T>
+    /// Allocates `data` with a reference count of 1.
+    pub fn new(data: T) -> Self {
+        // SAFETY: The pointer has just been returned by `raw` and the count is
+        //   initialized exactly once.
+        unsafe {
+            let arc = Self::from_raw(Self::raw(data));
+            arc.init_count(1);
+            arc
+        }
+    }
+
+    /// Allocates an `ArcInner` with a reference count of 1 and initializes the
+    /// data in-place by calling `init`.
+    ///
+    /// Unlike [`new`][new], the data is never moved, i.e. it doesn't have to
+    /// fit on the stack.
+    ///
+    /// # Safety
+    ///
+    /// `init` must initialize the data, unless it panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let arc = unsafe {
+    ///     impatience::Arc::<[u64; 1 << 17]>::new_with(|slot| {
+    ///         slot.as_mut_ptr().write_bytes(0, 1);
+    ///     })
+    /// };
+    ///
+    /// assert!(arc.iter().all(|&x| x == 0));
+    /// ```
+    ///
+    /// [new]: struct.Arc.html#method.new
+    pub unsafe fn new_with<F>(init: F) -> Self
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        let arc = Self::from_raw(Self::raw_with_in(init, std::ptr::null()));
+        arc.init_count(1);
+        arc
+    }
+
+    /// Allocates an `ArcInner` with a reference count of 1, but leaves the
+    /// data uninitialized.
+    ///
+    /// The data can be written through [`get_mut_unchecked`][get_mut] before
+    /// calling [`assume_init`][assume_init].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut arc = impatience::Arc::<u32>::new_uninit();
+    ///
+    /// let arc = unsafe {
+    ///     impatience::Arc::get_mut_unchecked(&mut arc).write(5);
+    ///     arc.assume_init()
+    /// };
+    ///
+    /// assert_eq!(*arc, 5);
+    /// ```
+    ///
+    /// [get_mut]: struct.Arc.html#method.get_mut_unchecked
+    /// [assume_init]: struct.Arc.html#method.assume_init
+    pub fn new_uninit() -> Arc<std::MaybeUninit<T>> {
+        // SAFETY: A `MaybeUninit<T>` doesn't need to be initialized.
+        unsafe { Arc::new_with(|_| {}) }
+    }
+
+    /// Consumes the `Arc` without decrementing the reference count and
+    /// returns a raw pointer to its allocation.
+    ///
+    /// The reference is released by dropping an `Arc` constructed with
+    /// [`from_raw`][from_raw].
+    ///
+    /// [from_raw]: struct.Arc.html#method.from_raw
+    pub fn into_raw(this: Self) -> RawArc<T> {
+        let raw = RawArc { inner: this.inner };
+
+        std::forget(this);
+
+        raw
+    }
+
+    /// Moves the data out, if no other `Arc` points to the same allocation.
+    /// Otherwise, `this` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let arc = impatience::Arc::new(5u32);
+    /// let clone = arc.clone();
+    ///
+    /// let arc = impatience::Arc::try_unwrap(arc).unwrap_err();
+    /// drop(clone);
+    ///
+    /// assert_eq!(impatience::Arc::try_unwrap(arc).ok(), Some(5));
+    /// ```
+    pub fn try_unwrap(this: Self) -> std::Result<T, Self> {
+        // Synchronizes with the decrements of dropped `Arc`s like `Drop::drop`
+        // for `Arc`.
+        if this
+            .inner()
+            .count
+            .strong
+            .compare_exchange(1, 0, std::Acquire, std::Relaxed)
+            .is_err()
+        {
+            return std::Err(this);
+        }
+
+        // SAFETY: The strong count has reached 0, i.e. `Weak`s can't upgrade
+        //   anymore and the data is moved out exactly once.
+        unsafe { std::Ok(Self::take(this)) }
+    }
+
+    /// Drops `this` and moves the data out, if it has been the last `Arc`
+    /// pointing to the allocation.
+    ///
+    /// Unlike [`try_unwrap`][try_unwrap], exactly one of several `Arc`s
+    /// dropped concurrently by `into_inner` returns the data.
+    ///
+    /// [try_unwrap]: struct.Arc.html#method.try_unwrap
+    pub fn into_inner(this: Self) -> std::Option<T> {
+        // See: `Drop::drop` for `Arc`
+        if 1 != this.inner().count.strong.fetch_sub(1, std::Release) {
+            std::forget(this);
+
+            return std::None;
+        }
+
+        std::fence(std::Acquire);
+
+        // SAFETY: See: `try_unwrap`
+        unsafe { std::Some(Self::take(this)) }
+    }
+
+    /// Moves the data out and releases the weak reference of the `Arc`s.
+    ///
+    /// # Safety
+    ///
+    /// The strong count must have reached 0 by releasing the reference of
+    /// `this`.
+    unsafe fn take(this: Self) -> T {
+        let inner = this.inner.as_ptr();
+
+        std::forget(this);
+
+        let data = std::ptr::read(&(*inner).data);
+        Self::release_weak(inner);
+
+        data
+    }
+
+    /// Creates an empty pool for the allocations of `Arc<T>::raw`.
+    ///
+    /// Free blocks are linked through the memory of the pointer to the pool,
+    /// which isn't accessed while an `ArcInner` is free. See: `Arc::free`
+    pub(crate) fn pool() -> crate::Pool {
+        crate::Pool::new(
+            std::Layout::new::<ArcInner<T>>(),
+            std::size_of::<crate::Align128<Counts>>(),
+        )
+    }
+
+    pub fn raw(data: T) -> RawArc<T> {
+        // SAFETY: A null pointer doesn't refer to any pool.
+        unsafe { Self::raw_in(data, std::ptr::null()) }
+    }
+
+    /// Like [`raw`][raw], but the memory is taken from and returned to `pool`,
+    /// unless it's null.
+    ///
+    /// # Safety
+    ///
+    /// `pool` must either be null or point to a pool created by
+    /// [`Arc<T>::pool`][pool], which outlives the returned allocation.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    /// [pool]: struct.Arc.html#method.pool
+    pub(crate) unsafe fn raw_in(
+        data: T,
+        pool: *const crate::Pool,
+    ) -> RawArc<T> {
+        let raw = Self::raw_uninit_in(pool);
+
+        std::ptr::write(std::ptr::addr_of_mut!((*raw.as_mut()).data), data);
+
+        raw
+    }
+
+    /// Like [`raw_in`][raw_in], but the data is initialized in-place by
+    /// `init`, i.e. it's never moved.
+    ///
+    /// # Safety
+    ///
+    /// See [`raw_in`][raw_in]. Additionally, `init` must initialize the data,
+    /// unless it panics. The memory is freed in that case.
+    ///
+    /// [raw_in]: struct.Arc.html#method.raw_in
+    pub(crate) unsafe fn raw_with_in<F>(
+        init: F,
+        pool: *const crate::Pool,
+    ) -> RawArc<T>
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        let raw = Self::raw_uninit_in(pool);
+        let unfinished = Unfinished { raw };
+
+        init(
+            &mut *(std::ptr::addr_of_mut!((*raw.as_mut()).data)
+                as *mut std::MaybeUninit<T>),
+        );
+
+        std::forget(unfinished);
+
+        raw
+    }
+
+    /// Allocates an `ArcInner<T>` like [`raw_in`][raw_in], but leaves the
+    /// data uninitialized.
+    ///
+    /// [raw_in]: struct.Arc.html#method.raw_in
+    unsafe fn raw_uninit_in(pool: *const crate::Pool) -> RawArc<T> {
+        let recycled = if pool.is_null() {
+            std::null_mut()
+        } else {
+            (*pool).take() as *mut ArcInner<T>
         };
 
-        unsafe {
+        let uninit = if recycled.is_null() {
+            let uninit = if pool.is_null() {
+                let layout = std::Layout::new::<ArcInner<T>>();
+                let memory = std::alloc(layout);
+
+                if memory.is_null() {
+                    std::handle_alloc_error(layout);
+                }
+
+                memory
+            } else {
+                (*pool).alloc()
+            } as *mut ArcInner<T>;
+
             std::ptr::write(
-                uninit,
-                ArcInner {
-                    count: crate::Align128(std::AtomicIsize::new(0)),
-                    data,
-                },
+                std::ptr::addr_of_mut!((*uninit).count),
+                crate::Align128(Counts::new(COUNT_BIAS)),
             );
+
+            uninit
+        } else {
+            recycled
+        };
+
+        std::ptr::write(std::ptr::addr_of_mut!((*uninit).pool), pool);
+        std::ptr::write(std::ptr::addr_of_mut!((*uninit).version), 0);
+        std::ptr::write(
+            std::ptr::addr_of_mut!((*uninit).layout),
+            std::Layout::new::<ArcInner<T>>(),
+        );
+        #[cfg(debug_assertions)]
+        std::ptr::write(std::ptr::addr_of_mut!((*uninit).tag), Tag::of::<T>());
+
+        if !recycled.is_null() {
+            // Readers may access the counts of a block, while it's in the
+            // pool. Storing the strong count last keeps them from acquiring a
+            // reference, before the other fields have been written. See:
+            // `Arc::increment_count_if_alive`
+            //
+            // Blocks are returned to the pool with a strong count of 0, which
+            // readers never increment, so no reference is erased here. See:
+            // `Arc::free_unshared`
+            {
+                use std::panic;
+                std::debug_assert!(
+                    (*recycled).count.0.strong.load(std::SeqCst) == 0
+                );
+            }
+
+            (*recycled).count.0.weak.store(1, std::SeqCst);
+            (*recycled).count.0.strong.store(COUNT_BIAS, std::SeqCst);
         }
 
-        uninit as _
+        RawArc {
+            inner: std::NonNull::new_unchecked(uninit),
+        }
     }
 
-    /// Constructs an `Arc<T>` from a raw pointer.
-    ///
-    /// The raw pointer must have been previously returned by a call to
-    /// [`Arc<U>::raw`][raw] where `U` must have the same size and
-    /// alignment as `T`. This is trivially true if `U` is `T`.
-    /// Note that if `U` is not `T` but has the same size and alignment, this is
-    /// basically like transmuting references of different types. See
-    /// `std::mem::transmute` for more information on what
-    /// restrictions apply in this case.
+    /// Constructs an `Arc<T>` from a raw pointer returned by
+    /// [`Arc<T>::raw`][raw] or [`Arc<T>::into_raw`][into_raw].
     ///
     /// The user of `from_raw` has to make sure a specific value of `T` is only
-    /// dropped once.
+    /// dropped once, i.e. that the raw pointer owns a reference, which is
+    /// released by dropping the returned `Arc<T>`.
     ///
     /// This function is unsafe because improper use may lead to memory unsafety,
     /// even if the returned `Arc<T>` is never accessed.
     ///
+    /// # Safety
+    ///
+    /// `raw` must have been returned by `Arc<T>::raw` or `Arc<T>::into_raw`
+    /// and must own a reference to the allocation, i.e. the allocation can't
+    /// have been freed, yet. The reference count of an allocation returned by
+    /// `Arc<T>::raw` must be initialized with [`init_count`][init_count]
+    /// before the last reference is released. Otherwise, it's never freed.
+    ///
+    /// # Panics
+    ///
+    /// Debug builds panic, if the raw pointer doesn't point to an allocation
+    /// of an `Arc<T>`. See [`RawArc`][raw_arc].
+    ///
     /// [raw]: struct.Arc.html#method.raw
-    pub unsafe fn from_raw(ptr: *const ()) -> Self {
+    /// [into_raw]: struct.Arc.html#method.into_raw
+    /// [init_count]: struct.Arc.html#method.init_count
+    /// [raw_arc]: struct.RawArc.html
+    pub unsafe fn from_raw(raw: RawArc<T>) -> Self {
+        raw.check();
+
         Self {
-            inner: std::NonNull::new_unchecked(ptr as _),
+            inner: raw.inner,
             phantom: std::PhantomData,
         }
     }
@@ -60,15 +624,146 @@ impl<T> Arc<T> {
     ///
     /// # Safety
     ///
-    /// This method **must not** be called more than once. The provided count
-    /// **must** be at least 1.
+    /// `self` **must** have been constructed by [`from_raw`][from_raw] from a
+    /// pointer returned by [`Arc<T>::raw`][raw], whose count hasn't been
+    /// initialized, yet. The count of an `Arc` created in any other way, e.g.
+    /// by `Arc::new` or `From`, is already initialized. Calling this method on
+    /// it corrupts the count, i.e. the data is either dropped while it's still
+    /// referenced or never.
+    ///
+    /// This method **must not** be called more than once per allocation. The
+    /// provided count **must** be at least 1.
+    ///
+    /// [from_raw]: struct.Arc.html#method.from_raw
+    /// [raw]: struct.Arc.html#method.raw
     pub unsafe fn init_count(&self, count: isize) {
         {
             use std::panic;
             std::debug_assert!(count >= 1);
         }
 
-        self.inner.as_ref().count.fetch_add(count, std::Relaxed);
+        self.inner
+            .as_ref()
+            .count
+            .strong
+            .fetch_add(count - COUNT_BIAS, std::Relaxed);
+    }
+
+    /// Increments the reference count of the `ArcInner` behind a raw pointer
+    /// returned by [`Arc<T>::raw_in`][raw_in], unless it has reached 0.
+    /// Returns `true`, if the count has been incremented. The reference is
+    /// released by dropping an `Arc<T>` constructed with
+    /// [`from_raw`][from_raw].
+    ///
+    /// # Safety
+    ///
+    /// The `ArcInner` must have been allocated from a pool, which is still
+    /// alive. It may have been freed and even reused in the meantime.
+    ///
+    /// [raw_in]: struct.Arc.html#method.raw_in
+    /// [from_raw]: struct.Arc.html#method.from_raw
+    pub(crate) unsafe fn increment_count_if_alive(raw: RawArc<T>) -> bool {
+        // Only the count is accessed, which the pool doesn't overwrite.
+        let strong = &(*raw.as_mut()).count.0.strong;
+        let mut count = strong.load(std::SeqCst);
+
+        loop {
+            if count == 0 {
+                return false;
+            }
+
+            // A count other than 0 belongs to an `ArcInner`, which is alive,
+            // because the count is reset only after the other fields of a
+            // reused block have been written. See: `Arc::raw_uninit_in`
+            match strong.compare_exchange_weak(
+                count,
+                count + 1,
+                std::SeqCst,
+                std::SeqCst,
+            ) {
+                std::Ok(_) => {
+                    return true;
+                }
+                std::Err(count_current) => {
+                    count = count_current;
+                }
+            }
+        }
+    }
+
+    /// Returns the version of the data behind a raw pointer returned by
+    /// [`Arc<T>::raw`][raw].
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure, that the memory isn't freed while reading the
+    /// version.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    pub(crate) unsafe fn version_from_raw(raw: RawArc<T>) -> u64 {
+        (*raw.as_mut()).version
+    }
+
+    /// Sets the version of the data behind a raw pointer returned by
+    /// [`Arc<T>::raw`][raw].
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive access to the `ArcInner`, e.g. because
+    /// it hasn't been shared, yet.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    pub(crate) unsafe fn set_version_raw(raw: RawArc<T>, version: u64) {
+        (*raw.as_mut()).version = version;
+    }
+
+    /// Moves the data out of the `ArcInner` behind a raw pointer returned by
+    /// [`Arc<T>::raw`][raw] and frees the memory.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own the `ArcInner` and its reference count must not
+    /// have been initialized. Other threads may only hold references, which
+    /// they acquired with [`increment_count_if_alive`][increment] and are
+    /// about to release without accessing the data. The pointer must not be
+    /// used afterwards.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    /// [increment]: struct.Arc.html#method.increment_count_if_alive
+    pub(crate) unsafe fn take_from_raw(raw: RawArc<T>) -> T {
+        let inner = raw.as_mut();
+        let data = std::ptr::read(&(*inner).data);
+
+        Self::free_unshared(inner);
+
+        data
+    }
+
+    /// Borrows the data behind a raw pointer returned by
+    /// [`Arc<T>::raw`][raw].
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure, that the memory isn't freed for the duration of
+    /// the returned lifetime.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    pub(crate) unsafe fn data_ref_from_raw<'a>(raw: RawArc<T>) -> &'a T {
+        &(*raw.as_mut()).data
+    }
+
+    /// Mutably borrows the data behind a raw pointer returned by
+    /// [`Arc<T>::raw`][raw].
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive access to the `ArcInner` for the
+    /// duration of the returned lifetime.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    #[cfg(not(loom))]
+    pub(crate) unsafe fn data_mut_from_raw<'a>(raw: RawArc<T>) -> &'a mut T {
+        &mut (*raw.as_mut()).data
     }
 }
 
@@ -76,27 +771,589 @@ impl<T> Arc<T>
 where
     T: std::Copy,
 {
-    pub unsafe fn data_from_raw(ptr: *const ()) -> T {
-        (*(ptr as *const ArcInner<T>)).data
+    /// Returns a copy of the data behind a raw pointer returned by
+    /// [`Arc<T>::raw`][raw].
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure, that the memory isn't freed while copying the
+    /// data and that the data has been initialized.
+    ///
+    /// [raw]: struct.Arc.html#method.raw
+    pub unsafe fn data_from_raw(raw: RawArc<T>) -> T {
+        (*raw.as_mut()).data
+    }
+}
+
+impl<T> Arc<T>
+where
+    T: std::Clone,
+{
+    /// Mutably borrows the data, after cloning it into a new allocation, if
+    /// another `Arc` points to the same one.
+    ///
+    /// If only `Weak`s point to it, the data is moved into a new allocation
+    /// instead, i.e. the `Weak`s can't upgrade anymore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut table = impatience::Arc::new(vec![1u32, 2, 3]);
+    /// let previous = table.clone();
+    ///
+    /// impatience::Arc::make_mut(&mut table).push(4);
+    ///
+    /// assert_eq!(*previous, [1, 2, 3]);
+    /// assert_eq!(*table, [1, 2, 3, 4]);
+    /// ```
+    pub fn make_mut(this: &mut Self) -> &mut T {
+        let count = &this.inner().count;
+
+        // Synchronizes with the decrements of dropped `Arc`s like `Drop::drop`
+        // for `Arc`.
+        if count
+            .strong
+            .compare_exchange(1, 0, std::Acquire, std::Relaxed)
+            .is_err()
+        {
+            *this = Self::new(std::Clone::clone(&**this));
+        } else if count.weak.load(std::Acquire) == 1 {
+            // No `Weak` can be created or upgraded, while the strong count is
+            // 0 and `this` is borrowed mutably.
+            count.strong.store(1, std::Release);
+        } else {
+            // SAFETY: The strong count has reached 0 by releasing the
+            //   reference of `this`, which is replaced without dropping it.
+            unsafe {
+                let data = Self::take(std::ptr::read(this));
+                std::ptr::write(this, Self::new(data));
+            }
+        }
+
+        // SAFETY: `this` is unique, i.e. no one else can access the data,
+        //   while it's borrowed mutably.
+        unsafe { &mut (*this.inner.as_ptr()).data }
+    }
+}
+
+impl<T> Arc<std::MaybeUninit<T>> {
+    /// Mutably borrows the uninitialized data.
+    ///
+    /// # Safety
+    ///
+    /// No other `Arc` or `Weak` to the same allocation may be used for the
+    /// duration of the returned lifetime.
+    pub unsafe fn get_mut_unchecked(
+        this: &mut Self,
+    ) -> &mut std::MaybeUninit<T> {
+        &mut (*this.inner.as_ptr()).data
+    }
+
+    /// Converts to an `Arc<T>`.
+    ///
+    /// # Safety
+    ///
+    /// The data must have been initialized.
+    pub unsafe fn assume_init(self) -> Arc<T> {
+        // A `MaybeUninit<T>` has the same layout as a `T`.
+        let raw = RawArc::<T> {
+            inner: Self::into_raw(self).inner.cast(),
+        };
+
+        // The allocation is still tagged as one of an `Arc<MaybeUninit<T>>`.
+        #[cfg(debug_assertions)]
+        {
+            (*raw.as_mut()).tag = Tag::of::<T>();
+        }
+
+        Arc::from_raw(raw)
+    }
+}
+
+/// Frees the memory of an `ArcInner<T>`, whose data hasn't been initialized,
+/// unless it's forgotten.
+struct Unfinished<T> {
+    raw: RawArc<T>,
+}
+
+impl<T> std::Drop for Unfinished<T> {
+    fn drop(&mut self) {
+        // SAFETY: The `ArcInner` hasn't been published, yet, so its count
+        //   hasn't been initialized. See: `Arc::free_unshared`
+        unsafe { Arc::<T>::free_unshared(self.raw.as_mut()) };
+    }
+}
+
+impl<T> std::Clone for Arc<T>
+where
+    T: ?std::Sized,
+{
+    fn clone(&self) -> Self {
+        self.inner().count.strong.fetch_add(1, std::Relaxed);
+
+        Self {
+            inner: self.inner,
+            phantom: std::PhantomData,
+        }
+    }
+}
+
+impl<T> std::Deref for Arc<T>
+where
+    T: ?std::Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().data
     }
 }
 
-impl<T> std::Drop for Arc<T> {
+impl<T> std::Drop for Arc<T>
+where
+    T: ?std::Sized,
+{
     /// Decrements the read count of the inner `ArcInner`. If the count reaches 0,
-    /// the boxed `ArcInner` is dropped.
+    /// the data is dropped and the boxed `ArcInner` is freed or returned to its
+    /// pool.
     fn drop(&mut self) {
-        let prev_count =
-            unsafe { self.inner.as_ref().count.fetch_sub(1, std::Relaxed) };
+        // Every `Arc` may have accessed the data on a different thread. The
+        // decrement releases those accesses and the fence acquires all of
+        // them, before the data is dropped.
+        let prev_count = unsafe {
+            self.inner.as_ref().count.strong.fetch_sub(1, std::Release)
+        };
 
         if 1 == prev_count {
+            std::fence(std::Acquire);
+
             // A count of **exactly** 0 implies exclusive access to the boxed
             // `ArcInner` and ensures safe construction of the `Box` to drop it.
+            // The data is dropped in-place before freeing the memory, which
+            // happens exactly once, because only one `Arc` can observe the
+            // transition to 0. The memory is kept until all `Weak`s have been
+            // dropped, too.
             unsafe {
-                std::dealloc(
-                    self.inner.as_ptr() as _,
-                    std::Layout::new::<ArcInner<T>>(),
-                );
+                std::ptr::drop_in_place(&mut (*self.inner.as_ptr()).data);
+                Self::release_weak(self.inner.as_ptr());
+            }
+        }
+    }
+}
+
+impl<T> Arc<[T]> {
+    /// Allocates an `ArcInner<[T]>` with a reference count of 1 and moves
+    /// the elements of `iter` into it.
+    ///
+    /// # Safety
+    ///
+    /// `iter` must yield exactly `len` elements.
+    unsafe fn from_iter_exact<I>(iter: I, len: usize) -> Self
+    where
+        I: std::Iterator<Item = T>,
+    {
+        let layout = slice_layout::<T>(len).expect("the slice is too large");
+        let memory = std::alloc(layout);
+
+        if memory.is_null() {
+            std::handle_alloc_error(layout);
+        }
+
+        let inner = std::ptr::slice_from_raw_parts_mut(memory as *mut T, len)
+            as *mut ArcInner<[T]>;
+
+        std::ptr::write(
+            std::ptr::addr_of_mut!((*inner).count),
+            crate::Align128(Counts::new(1)),
+        );
+        std::ptr::write(
+            std::ptr::addr_of_mut!((*inner).pool),
+            std::ptr::null(),
+        );
+        std::ptr::write(std::ptr::addr_of_mut!((*inner).version), 0);
+        std::ptr::write(std::ptr::addr_of_mut!((*inner).layout), layout);
+        #[cfg(debug_assertions)]
+        std::ptr::write(std::ptr::addr_of_mut!((*inner).tag), Tag::of::<[T]>());
+
+        // Cloning or iterating may panic.
+        let mut filling = Filling {
+            memory,
+            layout,
+            elements: std::ptr::addr_of_mut!((*inner).data) as *mut T,
+            len: 0,
+        };
+
+        for element in iter {
+            std::ptr::write(filling.elements.add(filling.len), element);
+            filling.len += 1;
+        }
+
+        std::forget(filling);
+
+        Self {
+            inner: std::NonNull::new_unchecked(inner),
+            phantom: std::PhantomData,
+        }
+    }
+}
+
+impl<T> std::From<&[T]> for Arc<[T]>
+where
+    T: std::Clone,
+{
+    fn from(slice: &[T]) -> Self {
+        // SAFETY: The iterator yields every element of the slice.
+        unsafe {
+            Self::from_iter_exact(
+                std::Iterator::cloned(slice.iter()),
+                slice.len(),
+            )
+        }
+    }
+}
+
+impl<T> std::From<std::Vec<T>> for Arc<[T]> {
+    fn from(vec: std::Vec<T>) -> Self {
+        let len = vec.len();
+
+        // SAFETY: The iterator yields every element of the vector.
+        unsafe { Self::from_iter_exact(std::IntoIterator::into_iter(vec), len) }
+    }
+}
+
+impl<T> std::FromIterator<T> for Arc<[T]> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: std::IntoIterator<Item = T>,
+    {
+        let vec: std::Vec<T> =
+            std::Iterator::collect(std::IntoIterator::into_iter(iter));
+
+        <Self as std::From<std::Vec<T>>>::from(vec)
+    }
+}
+
+impl std::From<&str> for Arc<str> {
+    fn from(string: &str) -> Self {
+        let bytes = std::ManuallyDrop::new(
+            <Arc<[u8]> as std::From<&[u8]>>::from(string.as_bytes()),
+        );
+
+        // SAFETY: A `str` has the same layout as a `[u8]` and the bytes are
+        //   valid UTF-8.
+        Self {
+            inner: unsafe {
+                std::NonNull::new_unchecked(
+                    bytes.inner.as_ptr() as *mut ArcInner<str>
+                )
+            },
+            phantom: std::PhantomData,
+        }
+    }
+}
+
+impl std::From<std::String> for Arc<str> {
+    fn from(string: std::String) -> Self {
+        <Self as std::From<&str>>::from(string.as_str())
+    }
+}
+
+/// Returns the layout of an `ArcInner<[T]>` of `len` elements or `None`, if
+/// it would be too large.
+fn slice_layout<T>(len: usize) -> std::Option<std::Layout> {
+    let layout = std::Layout::new::<crate::Align128<Counts>>();
+    let (layout, _) = layout
+        .extend(std::Layout::new::<*const crate::Pool>())
+        .ok()?;
+    let (layout, _) = layout.extend(std::Layout::new::<u64>()).ok()?;
+    let (layout, _) = layout.extend(std::Layout::new::<std::Layout>()).ok()?;
+    #[cfg(debug_assertions)]
+    let (layout, _) = layout.extend(std::Layout::new::<Tag>()).ok()?;
+    let (layout, _) = layout.extend(std::Layout::array::<T>(len).ok()?).ok()?;
+
+    std::Some(layout.align_to(128).ok()?.pad_to_align())
+}
+
+/// Drops the elements written to a new `ArcInner<[T]>` and frees its memory,
+/// unless it's forgotten.
+struct Filling<T> {
+    memory: *mut u8,
+    layout: std::Layout,
+    elements: *mut T,
+    len: usize,
+}
+
+impl<T> std::Drop for Filling<T> {
+    fn drop(&mut self) {
+        // SAFETY: The first `len` elements have been written, but the slice
+        //   hasn't been shared, yet.
+        unsafe {
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                self.elements,
+                self.len,
+            ));
+            std::dealloc(self.memory, self.layout);
+        }
+    }
+}
+
+/// A raw pointer to the allocation of an [`Arc<T>`][arc], as returned by
+/// [`Arc::raw`][raw] and [`Arc::into_raw`][into_raw].
+///
+/// Unlike a `*const ()`, it can only be turned back into an `Arc` of the same
+/// type. The allocation is always aligned to 128 bytes, i.e. the lowest 7
+/// bits of its address are 0.
+///
+/// Debug builds mark each allocation with a magic number and the name of the
+/// type of its data, which are checked by [`Arc::from_raw`][from_raw] and
+/// [`RawArc::from_ptr`][from_ptr]. That catches pointers, which haven't been
+/// returned by `Arc::raw` or point to an allocation of another type, on a
+/// best effort basis.
+///
+/// # Examples
+///
+/// ```
+/// let raw = impatience::Arc::raw(5u32);
+/// let ptr = raw.as_ptr();
+///
+/// assert_eq!(ptr as usize % 128, 0);
+///
+/// let arc = unsafe {
+///     impatience::Arc::from_raw(impatience::RawArc::<u32>::from_ptr(ptr))
+/// };
+///
+/// // The reference count of an allocation returned by `Arc::raw` must be
+/// // initialized, before it can be freed.
+/// unsafe { arc.init_count(1) };
+///
+/// assert_eq!(*arc, 5);
+/// ```
+///
+/// [arc]: struct.Arc.html
+/// [raw]: struct.Arc.html#method.raw
+/// [into_raw]: struct.Arc.html#method.into_raw
+/// [from_raw]: struct.Arc.html#method.from_raw
+/// [from_ptr]: struct.RawArc.html#method.from_ptr
+pub struct RawArc<T> {
+    inner: std::NonNull<ArcInner<T>>,
+}
+
+impl<T> RawArc<T> {
+    /// Returns the address of the allocation.
+    pub fn as_ptr(self) -> *const () {
+        self.inner.as_ptr() as _
+    }
+
+    /// Converts an address returned by [`as_ptr`][as_ptr] back.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `as_ptr` of a `RawArc<T>`, whose
+    /// allocation hasn't been freed, yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `ptr` is null or isn't aligned to 128 bytes. Debug builds
+    /// also panic, if it doesn't point to an allocation of an `Arc<T>`.
+    ///
+    /// [as_ptr]: struct.RawArc.html#method.as_ptr
+    pub unsafe fn from_ptr(ptr: *const ()) -> Self {
+        {
+            use std::panic;
+            std::assert!(
+                !ptr.is_null() && ptr as usize & 127 == 0,
+                "pointer is null or isn't aligned to 128 bytes"
+            );
+        }
+
+        let raw = Self::from_ptr_unchecked(ptr);
+
+        raw.check();
+
+        raw
+    }
+
+    /// Like [`from_ptr`][from_ptr], but nothing is checked, i.e. the memory
+    /// may have been freed already.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and aligned to 128 bytes.
+    ///
+    /// [from_ptr]: struct.RawArc.html#method.from_ptr
+    pub(crate) unsafe fn from_ptr_unchecked(ptr: *const ()) -> Self {
+        Self {
+            inner: std::NonNull::new_unchecked(ptr as _),
+        }
+    }
+
+    fn as_mut(self) -> *mut ArcInner<T> {
+        self.inner.as_ptr()
+    }
+
+    /// Panics in debug builds, unless the pointer points to an allocation of
+    /// an `ArcInner<T>`.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must point to live memory of at least the size of the
+    /// header of an `ArcInner`, i.e. it's only called by functions, which
+    /// require it to point to an allocation, which hasn't been freed, yet.
+    unsafe fn check(self) {
+        #[cfg(debug_assertions)]
+        {
+            use std::panic;
+            let tag = std::ptr::addr_of!((*self.as_mut()).tag);
+
+            // The type name is only read, if the magic number has been found,
+            // because arbitrary memory doesn't hold a valid reference.
+            std::assert!(
+                std::ptr::read(std::ptr::addr_of!((*tag).magic)) == MAGIC,
+                "pointer doesn't point to the allocation of an `Arc`"
+            );
+
+            let tag = *tag;
+
+            std::assert!(
+                tag == Tag::of::<T>(),
+                "pointer points to the allocation of an `Arc<{}>`, not of an \
+                 `Arc<{}>`",
+                tag.type_name,
+                std::type_name::<T>(),
+            );
+        }
+    }
+}
+
+impl<T> std::Clone for RawArc<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> std::Copy for RawArc<T> {}
+
+impl<T> std::PartialEq for RawArc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T> std::Eq for RawArc<T> {}
+
+impl<T> std::fmt::Debug for RawArc<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Pointer::fmt(&self.inner, f)
+    }
+}
+
+/// A reference to the data of an [`Arc`][arc], which doesn't keep the data
+/// alive.
+///
+/// The data is dropped, once all `Arc`s have been dropped. The memory is kept,
+/// until all `Weak`s have been dropped, too.
+///
+/// # Examples
+///
+/// ```
+/// let arc = impatience::Arc::new(5u32);
+/// let weak = impatience::Arc::downgrade(&arc);
+///
+/// assert_eq!(weak.upgrade().map(|arc| *arc), Some(5));
+///
+/// drop(arc);
+///
+/// assert!(weak.upgrade().is_none());
+/// ```
+///
+/// [arc]: struct.Arc.html
+pub struct Weak<T>
+where
+    T: ?std::Sized,
+{
+    inner: std::NonNull<ArcInner<T>>,
+    phantom: std::PhantomData<ArcInner<T>>,
+}
+
+// SAFETY: See: `Arc`
+unsafe impl<T> std::Send for Weak<T> where T: ?std::Sized + std::Send + std::Sync
+{}
+unsafe impl<T> std::Sync for Weak<T> where T: ?std::Sized + std::Send + std::Sync
+{}
+
+impl<T> Weak<T>
+where
+    T: ?std::Sized,
+{
+    /// Returns a new `Arc` to the data, unless it has been dropped already.
+    pub fn upgrade(&self) -> std::Option<Arc<T>> {
+        let strong = &self.count().strong;
+        let mut count = strong.load(std::Relaxed);
+
+        loop {
+            if count == 0 {
+                return std::None;
+            }
+
+            // The data is only dropped, once the count has reached 0, which
+            // it never leaves again.
+            match strong.compare_exchange_weak(
+                count,
+                count + 1,
+                std::Relaxed,
+                std::Relaxed,
+            ) {
+                std::Ok(_) => {
+                    return std::Some(Arc {
+                        inner: self.inner,
+                        phantom: std::PhantomData,
+                    });
+                }
+                std::Err(count_current) => {
+                    count = count_current;
+                }
             }
         }
     }
+
+    /// Returns the number of `Arc`s pointing to the same allocation.
+    pub fn strong_count(&self) -> usize {
+        self.count().strong.load(std::SeqCst) as usize
+    }
+
+    /// Returns `true`, if both `Weak`s point to the same allocation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.inner.as_ptr() as *const u8 == other.inner.as_ptr() as *const u8
+    }
+
+    /// Borrows the reference counts without borrowing the data, which may
+    /// have been dropped already.
+    fn count(&self) -> &Counts {
+        // SAFETY: The memory isn't freed, while this reference is alive.
+        unsafe { &(*self.inner.as_ptr()).count.0 }
+    }
+}
+
+impl<T> std::Clone for Weak<T>
+where
+    T: ?std::Sized,
+{
+    fn clone(&self) -> Self {
+        self.count().weak.fetch_add(1, std::Relaxed);
+
+        Self {
+            inner: self.inner,
+            phantom: std::PhantomData,
+        }
+    }
+}
+
+impl<T> std::Drop for Weak<T>
+where
+    T: ?std::Sized,
+{
+    fn drop(&mut self) {
+        // SAFETY: The weak reference of this `Weak` is released.
+        unsafe { Arc::release_weak(self.inner.as_ptr()) };
+    }
 }
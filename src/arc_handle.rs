@@ -1,5 +1,6 @@
 use crate::std;
 use crate::Arc;
+use crate::RawArc;
 
 const BITS_PER_BYTE: usize = 8;
 const LOG_2_128: usize = 7;
@@ -7,19 +8,22 @@ const PTR_BIT_MASK: usize = usize::MAX >> LOG_2_128;
 const USIZE_BITS: usize = std::size_of::<usize>() * BITS_PER_BYTE;
 const DATA_BIT_SHIFT: usize = USIZE_BITS - LOG_2_128;
 
-fn raw_arc_handle_from_ptr(ptr: *const ()) -> usize {
-    ptr as usize >> LOG_2_128
+fn raw_arc_handle_from_ptr<T>(raw_arc: RawArc<T>) -> usize {
+    raw_arc.as_ptr() as usize >> LOG_2_128
 }
 
-fn raw_arc_handle_ptr(value: usize) -> *const () {
-    (value << LOG_2_128) as _
+fn raw_arc_handle_ptr<T>(value: usize) -> RawArc<T> {
+    // SAFETY: Handles always hold the address of an `ArcInner<T>`, which is
+    //   aligned to 128 bytes. The memory isn't read, i.e. it may have been
+    //   freed already.
+    unsafe { RawArc::from_ptr_unchecked((value << LOG_2_128) as _) }
 }
 
 fn raw_arc_handle_count(value: usize) -> isize {
     (value >> DATA_BIT_SHIFT) as _
 }
 
-fn raw_arc_handle_inc_count(value: usize) -> usize {
+fn raw_arc_handle_inc_count(value: usize) -> std::Option<usize> {
     fn checked_inc(value: usize) -> std::Option<usize> {
         if value < 127 {
             std::Some(value.checked_add(1).unwrap_or_else(|| {
@@ -31,8 +35,8 @@ fn raw_arc_handle_inc_count(value: usize) -> usize {
         }
     }
 
-    (checked_inc(value >> DATA_BIT_SHIFT).unwrap() << DATA_BIT_SHIFT)
-        | (value & PTR_BIT_MASK)
+    checked_inc(value >> DATA_BIT_SHIFT)
+        .map(|count| (count << DATA_BIT_SHIFT) | (value & PTR_BIT_MASK))
 }
 
 fn raw_arc_handle_dec_count(value: usize) -> usize {
@@ -79,51 +83,251 @@ fn raw_arc_handle_dec_count(value: usize) -> usize {
 // registered when the writer obtained the raw handle, the counter will be
 // increased by 101 by the writer and each reader + the writer decreases the
 // counter by 1, thus reaching 0, eventually and deallocating the memory.
-pub struct ArcHandle<T>
-where
-    T: std::Copy,
-{
+//
+// The 7 bits limit the number of readers, which are registered at the same
+// time, to 127. Guards hold on to their registration as well. Readers, which
+// find the read count saturated, increment the inner Arc's counter instead,
+// unless it has reached 0 already, and check, whether the raw handle still
+// points to the inner Arc afterwards. The memory may have been freed in the
+// meantime, but it's never returned to the global allocator before the cell
+// is dropped. See: `Pool`. The counter starts out biased, so it can't reach 0
+// before the writer initialized it. See: `Arc::raw`. Readers only take this
+// path, if they have to, because the read count is on the cache line they
+// access anyway.
+//
+// The memory of the inner Arcs is recycled through a pool shared by all
+// handles, which are swapped in and out of the same cell. See: `Pool`
+//
+// Each inner Arc carries a version, which is one greater than the version of
+// the data it replaced. Writers hold a reference to the data they replace and
+// only swap in their handle, if the raw pointer hasn't changed in the
+// meantime. Therefore, no two handles are published with the same version.
+pub struct ArcHandle<T> {
     handle: std::AtomicUsize,
+    pool: *const crate::Pool,
     phantom: std::PhantomData<Arc<T>>,
 }
 
-impl<T> ArcHandle<T>
-where
-    T: std::Copy,
-{
-    pub fn new(data: T) -> Self {
-        let ptr = Arc::raw(data);
-        let raw_handle = raw_arc_handle_from_ptr(ptr);
+impl<T> ArcHandle<T> {
+    /// Creates a handle to `data`, which is allocated from `pool`.
+    ///
+    /// # Safety
+    ///
+    /// `pool` must point to a pool created by `Arc::<T>::pool`, which
+    /// outlives the handle and all handles swapped with it. Unlike for
+    /// `Arc::raw_in`, it must not be null, because readers rely on the memory
+    /// of the inner Arcs staying allocated. See: `ArcHandle::acquire_counted`
+    pub unsafe fn new(data: T, pool: *const crate::Pool) -> Self {
+        Self::from_raw_arc(Arc::raw_in(data, pool), pool)
+    }
+
+    /// Like [`new`][new], but the data is initialized in-place by `init`.
+    ///
+    /// # Safety
+    ///
+    /// See [`new`][new]. Additionally, `init` must initialize the data, unless
+    /// it panics.
+    ///
+    /// [new]: struct.ArcHandle.html#method.new
+    pub unsafe fn new_with<F>(init: F, pool: *const crate::Pool) -> Self
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        Self::from_raw_arc(Arc::raw_with_in(init, pool), pool)
+    }
+
+    /// Creates a handle to `data`, which shares the pool of `self`.
+    pub fn new_sibling(&self, data: T) -> Self {
+        // SAFETY: The pool outlives `self` and all handles swapped with it.
+        unsafe { Self::new(data, self.pool) }
+    }
+
+    /// Like [`new_sibling`][new_sibling], but the data is initialized
+    /// in-place by `init`.
+    ///
+    /// # Safety
+    ///
+    /// `init` must initialize the data, unless it panics.
+    ///
+    /// [new_sibling]: struct.ArcHandle.html#method.new_sibling
+    pub unsafe fn new_sibling_with<F>(&self, init: F) -> Self
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        Self::new_with(init, self.pool)
+    }
+
+    unsafe fn from_raw_arc(
+        raw_arc: RawArc<T>,
+        pool: *const crate::Pool,
+    ) -> Self {
+        let raw_handle = raw_arc_handle_from_ptr(raw_arc);
         let handle = std::AtomicUsize::new(raw_handle);
 
         Self {
             handle,
+            pool,
             phantom: std::PhantomData,
         }
     }
 
+    pub fn pool(&self) -> *const crate::Pool {
+        self.pool
+    }
+
+    /// Swaps `other` into `self`. `other` is published with the version
+    /// following the one of the data it replaces.
     pub fn swap(&self, other: &mut Self) {
-        #[cfg(loom)]
-        {
-            let raw_handle = unsafe { other.handle.unsync_load() };
-            let swap = self.handle.swap(raw_handle, std::SeqCst);
-            other.handle.with_mut(|value| *value = swap);
+        loop {
+            if self.publish_accessed(self.acquire(), other) {
+                return;
+            }
         }
+    }
 
-        #[cfg(not(loom))]
-        {
-            let raw_handle_mut = other.handle.get_mut();
-            *raw_handle_mut = self.handle.swap(*raw_handle_mut, std::SeqCst);
+    /// Swaps `other` into `self`, if the version of the data `self` points to
+    /// is equal to `expected`. Returns `true`, if `other` has been swapped in.
+    ///
+    /// `other` is left untouched, if the comparison fails.
+    pub fn swap_if_version(&self, expected: u64, other: &mut Self) -> bool {
+        let access = self.acquire();
+
+        // SAFETY: See: `ArcHandle::read`
+        let version =
+            unsafe { Arc::<T>::version_from_raw(access.raw_arc_ptr()) };
+
+        if version != expected {
+            self.release(access);
+
+            return false;
         }
+
+        // A failed publication implies, that another version has been
+        // published in the meantime.
+        self.publish_accessed(access, other)
     }
 
-    pub fn get(&self) -> T {
+    /// Returns the version of the data.
+    pub fn version(&self) -> u64 {
+        let access = self.acquire();
+
+        // SAFETY: See: `ArcHandle::read`
+        let version =
+            unsafe { Arc::<T>::version_from_raw(access.raw_arc_ptr()) };
+
+        self.release(access);
+
+        version
+    }
+
+    /// Consumes a handle, which has never been swapped with another handle,
+    /// and returns the data it points to.
+    pub fn into_unpublished_inner(self) -> T {
+        // SAFETY: The handle has never been shared, i.e. no one else has
+        //   access to the inner Arc.
+        unsafe { self.into_unique_inner() }
+    }
+
+    /// Consumes a handle and returns the data it points to.
+    ///
+    /// # Safety
+    ///
+    /// No one else may have access to the inner Arc, i.e. the handle must
+    /// either never have been shared or all guards borrowing from it must
+    /// have been dropped.
+    pub unsafe fn into_unique_inner(mut self) -> T {
+        let raw_handle = self.load_mut();
+        std::forget(self);
+
+        Arc::take_from_raw(raw_arc_handle_ptr(raw_handle))
+    }
+
+    /// Mutably borrows the data the handle points to. The data is assigned
+    /// the next version, since it may be changed through the borrow.
+    #[cfg(not(loom))]
+    pub fn get_mut(&mut self) -> &mut T {
+        let raw_arc_ptr = raw_arc_handle_ptr(self.load_mut());
+
+        // SAFETY: Guards borrow the handle, i.e. no one else has access to
+        //   the inner Arc while the handle is borrowed mutably.
+        unsafe {
+            Arc::<T>::set_version_raw(
+                raw_arc_ptr,
+                Arc::<T>::version_from_raw(raw_arc_ptr).wrapping_add(1),
+            );
+
+            Arc::data_mut_from_raw(raw_arc_ptr)
+        }
+    }
+
+    /// Acquires a read access to the data, which is released when the
+    /// returned guard is dropped.
+    pub fn load_guard(&self) -> Guard<'_, T> {
+        Guard {
+            handle: self,
+            access: self.acquire(),
+        }
+    }
+
+    /// Registers the caller as a reader or, if the read count is saturated,
+    /// acquires a reference to the inner Arc. Never waits for other readers.
+    ///
+    /// The returned access has to be passed on to `release`.
+    fn acquire(&self) -> Access<T> {
+        match self.try_register_reader() {
+            std::Some(raw_handle) => Access::Registered(raw_handle),
+            std::None => Access::Counted(self.acquire_counted()),
+        }
+    }
+
+    /// Acquires a reference to the inner Arc `self` points to without
+    /// registering as a reader.
+    fn acquire_counted(&self) -> RawArc<T> {
+        loop {
+            let raw_arc_ptr = raw_arc_handle_ptr(self.handle.load(std::SeqCst));
+
+            // SAFETY: The inner Arc has been allocated from the pool of
+            //   `self`, which outlives `self`. A count of 0 implies, that the
+            //   raw handle has been swapped out already.
+            if unsafe { Arc::<T>::increment_count_if_alive(raw_arc_ptr) } {
+                // The inner Arc may have been freed and reused for data,
+                // which hasn't been published, yet.
+                if raw_arc_handle_ptr(self.handle.load(std::SeqCst))
+                    == raw_arc_ptr
+                {
+                    return raw_arc_ptr;
+                }
+
+                // SAFETY: The reference has just been acquired.
+                std::drop(unsafe { Arc::<T>::from_raw(raw_arc_ptr) });
+            }
+        }
+    }
+
+    /// Releases an access acquired by `acquire`.
+    fn release(&self, access: Access<T>) {
+        match access {
+            Access::Registered(raw_handle) => {
+                self.unregister_reader(raw_handle)
+            }
+            Access::Counted(raw_arc_ptr) => {
+                // SAFETY: The reference has been acquired by
+                //   `acquire_counted`.
+                std::drop(unsafe { Arc::<T>::from_raw(raw_arc_ptr) });
+            }
+        }
+    }
+
+    /// Obtains a raw handle and increments the read count embedded in it.
+    ///
+    /// Returns the raw handle including the registration of the caller, which
+    /// has to be passed on to `unregister_reader`, or `None`, if the read count
+    /// is saturated.
+    fn try_register_reader(&self) -> std::Option<usize> {
         let mut raw_handle = self.handle.load(std::SeqCst);
-        let mut raw_handle_new;
 
-        // Obtain a raw handle and update the read count
         loop {
-            raw_handle_new = raw_arc_handle_inc_count(raw_handle);
+            let raw_handle_new = raw_arc_handle_inc_count(raw_handle)?;
 
             let result = self.handle.compare_exchange_weak(
                 raw_handle,
@@ -134,42 +338,35 @@ where
 
             match result {
                 std::Ok(_) => {
-                    break;
+                    return std::Some(raw_handle_new);
                 }
                 std::Err(raw_handle_current) => {
                     raw_handle = raw_handle_current;
                 }
             }
         }
+    }
 
-        let raw_arc_ptr = raw_arc_handle_ptr(raw_handle_new);
-
-        // SAFETY: The read count of the atomic variable has been incremented
-        //   and the memory is not freed before the internal counter has been
-        //   reduced to 0. See: `Drop::drop` for `Arc`
-        let data = unsafe { Arc::data_from_raw(raw_arc_ptr) };
+    fn unregister_reader(&self, mut raw_handle: usize) {
+        let raw_arc_ptr = raw_arc_handle_ptr(raw_handle);
 
         loop {
             let result = self.handle.compare_exchange(
-                raw_handle_new,
                 raw_handle,
+                raw_arc_handle_dec_count(raw_handle),
                 std::SeqCst,
                 std::SeqCst,
             );
 
             match result {
                 std::Ok(_) => {
-                    return data;
+                    return;
                 }
                 std::Err(raw_handle_current) => {
-                    let raw_handle_current = raw_handle_current;
-
                     if raw_arc_handle_ptr(raw_handle_current) == raw_arc_ptr {
                         // The raw pointer remains the same. That's why we try
                         // to decrement the count with updated values, again.
-                        raw_handle_new = raw_handle_current;
-                        raw_handle =
-                            raw_arc_handle_dec_count(raw_handle_current);
+                        raw_handle = raw_handle_current;
                     } else {
                         // The raw pointer has been swapped out, i.e. the read
                         // count embedded in the raw handle will be used to
@@ -182,29 +379,236 @@ where
                         //   yet. See: `Drop::drop` for `Arc`
                         std::drop(unsafe { Arc::<T>::from_raw(raw_arc_ptr) });
 
-                        return data;
+                        return;
                     }
                 }
             }
         }
     }
+
+    /// Swaps `other` into `self`, if `self` still points to `raw_arc_ptr`.
+    ///
+    /// Returns `true`, if `other` has been swapped in. The caller has to hold
+    /// a reference to the inner Arc or be registered as a reader to rule out
+    /// the memory being reused in the meantime.
+    fn publish(&self, raw_arc_ptr: RawArc<T>, other: &mut Self) -> bool {
+        let raw_handle_other = other.load_mut();
+        let mut raw_handle = self.handle.load(std::SeqCst);
+
+        while raw_arc_handle_ptr(raw_handle) == raw_arc_ptr {
+            let result = self.handle.compare_exchange(
+                raw_handle,
+                raw_handle_other,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                std::Ok(_) => {
+                    other.store_mut(raw_handle);
+
+                    return true;
+                }
+                std::Err(raw_handle_current) => {
+                    // Only the read count has changed, if the raw pointer
+                    // remains the same.
+                    raw_handle = raw_handle_current;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Publishes `other` with the version following the one of the data
+    /// behind `raw_arc_ptr`, if `self` still points to it. See: `publish`
+    fn publish_next(&self, raw_arc_ptr: RawArc<T>, other: &mut Self) -> bool {
+        // SAFETY: The caller holds a reference to the inner Arc behind
+        //   `raw_arc_ptr`. `other` hasn't been published, yet, i.e. no one
+        //   else has access to its inner Arc.
+        unsafe {
+            Arc::<T>::set_version_raw(
+                raw_arc_handle_ptr(other.load_mut()),
+                Arc::<T>::version_from_raw(raw_arc_ptr).wrapping_add(1),
+            );
+        }
+
+        self.publish(raw_arc_ptr, other)
+    }
+
+    /// Publishes `other` like `publish_next` with the data the caller has
+    /// acquired `access` to. The access is released either way.
+    fn publish_accessed(&self, access: Access<T>, other: &mut Self) -> bool {
+        let published = self.publish_next(access.raw_arc_ptr(), other);
+
+        match access {
+            Access::Registered(_) if published => {
+                // The registration has been swapped out along with the raw
+                // pointer, i.e. it's part of the read count `other` holds
+                // now. Removing it from there spares another atomic operation
+                // on `self`.
+                let raw_handle_other = other.load_mut();
+                other.store_mut(raw_arc_handle_dec_count(raw_handle_other));
+            }
+            _ => {
+                self.release(access);
+            }
+        }
+
+        published
+    }
+
+    fn load_mut(&mut self) -> usize {
+        #[cfg(loom)]
+        unsafe {
+            self.handle.unsync_load()
+        }
+        #[cfg(not(loom))]
+        *self.handle.get_mut()
+    }
+
+    fn store_mut(&mut self, raw_handle: usize) {
+        #[cfg(loom)]
+        self.handle.with_mut(|value| *value = raw_handle);
+        #[cfg(not(loom))]
+        {
+            *self.handle.get_mut() = raw_handle;
+        }
+    }
+}
+
+impl<T> ArcHandle<T>
+where
+    T: std::Clone,
+{
+    /// Consumes the handle and moves the data out, if no one else holds a
+    /// reference to it. Otherwise, the data is cloned.
+    ///
+    /// Readers, which registered themselves before the handle has been
+    /// swapped out, may still be reading the data. They are accounted for
+    /// like in `Drop::drop` for `ArcHandle`.
+    pub fn into_inner_or_clone(mut self) -> T {
+        let raw_handle = self.load_mut();
+        std::forget(self);
+
+        // SAFETY: The handle has been consumed and its count is initialized
+        //   exactly once. See: `Drop::drop` for `ArcHandle`
+        let arc = unsafe {
+            let arc = Arc::<T>::from_raw(raw_arc_handle_ptr(raw_handle));
+            arc.init_count(raw_arc_handle_count(raw_handle) + 1);
+
+            arc
+        };
+
+        Arc::try_unwrap(arc).unwrap_or_else(|arc| (*arc).clone())
+    }
 }
 
-impl<T> std::Drop for ArcHandle<T>
+impl<T> ArcHandle<T>
 where
     T: std::Copy,
 {
-    fn drop(&mut self) {
-        unsafe {
-            let raw_handle = {
-                #[cfg(loom)]
-                unsafe {
-                    self.handle.unsync_load()
+    pub fn get(&self) -> T {
+        self.read(self.acquire())
+    }
+
+    /// Like `get`, but returns `None` instead of acquiring a reference to the
+    /// inner Arc, if the read count is saturated.
+    pub fn try_get(&self) -> std::Option<T> {
+        self.try_register_reader()
+            .map(|raw_handle| self.read(Access::Registered(raw_handle)))
+    }
+
+    /// Returns a copy of the data and its version.
+    pub fn get_versioned(&self) -> (u64, T) {
+        let access = self.acquire();
+        let raw_arc_ptr = access.raw_arc_ptr();
+
+        // SAFETY: See: `ArcHandle::read`
+        let versioned = unsafe {
+            (
+                Arc::<T>::version_from_raw(raw_arc_ptr),
+                Arc::data_from_raw(raw_arc_ptr),
+            )
+        };
+
+        self.release(access);
+
+        versioned
+    }
+
+    fn read(&self, access: Access<T>) -> T {
+        // SAFETY: Either the read count of the atomic variable or the
+        //   internal counter has been incremented and the memory is not freed
+        //   before the internal counter has been reduced to 0. See:
+        //   `Drop::drop` for `Arc`
+        let data = unsafe { Arc::data_from_raw(access.raw_arc_ptr()) };
+
+        self.release(access);
+
+        data
+    }
+
+    /// Swaps `other` into `self`, if the data `self` points to is equal to
+    /// `current`. Returns the data `self` pointed to at the time of the
+    /// comparison.
+    ///
+    /// `other` is left untouched, if the comparison fails.
+    pub fn compare_exchange(
+        &self,
+        current: T,
+        other: &mut Self,
+    ) -> std::Result<T, T>
+    where
+        T: std::PartialEq,
+    {
+        loop {
+            let guard = self.load_guard();
+            let data = *guard;
+
+            if data != current {
+                return std::Err(data);
+            }
+
+            if self.publish_next(guard.access.raw_arc_ptr(), other) {
+                return std::Ok(data);
+            }
+        }
+    }
+
+    /// Repeatedly applies `f` to the data `self` points to and tries to swap
+    /// in a new handle to the result, until it succeeds or `f` returns
+    /// `None`. Returns the data `f` has been applied to last.
+    pub fn fetch_update<F>(&self, mut f: F) -> std::Result<T, T>
+    where
+        F: std::FnMut(T) -> std::Option<T>,
+    {
+        loop {
+            let guard = self.load_guard();
+            let data = *guard;
+
+            match f(data) {
+                std::Some(new) => {
+                    if self.publish_next(
+                        guard.access.raw_arc_ptr(),
+                        &mut self.new_sibling(new),
+                    ) {
+                        return std::Ok(data);
+                    }
+                }
+                std::None => {
+                    return std::Err(data);
                 }
-                #[cfg(not(loom))]
-                *self.handle.get_mut()
-            };
+            }
+        }
+    }
+}
+
+impl<T> std::Drop for ArcHandle<T> {
+    fn drop(&mut self) {
+        let raw_handle = self.load_mut();
 
+        unsafe {
             // SAFETY: Arc can and must be constructed in two ways only:
             // 1) By `ArcHandle::get`, if and only if the read count from the raw
             //    Arc handle could not be decremented, because it was swapped out.
@@ -235,6 +639,66 @@ where
     }
 }
 
+/// A read access to the data of an `ArcHandle`, which keeps the memory from
+/// being freed. See: `ArcHandle::acquire`
+enum Access<T> {
+    /// The caller is registered as a reader with the contained raw handle.
+    Registered(usize),
+    /// The caller holds a reference to the inner Arc, because the read count
+    /// has been saturated.
+    Counted(RawArc<T>),
+}
+
+impl<T> Access<T> {
+    fn raw_arc_ptr(&self) -> RawArc<T> {
+        match *self {
+            Access::Registered(raw_handle) => raw_arc_handle_ptr(raw_handle),
+            Access::Counted(raw_arc_ptr) => raw_arc_ptr,
+        }
+    }
+}
+
+impl<T> std::Clone for Access<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> std::Copy for Access<T> {}
+
+/// A read access to the value of an `ArcHandle`.
+///
+/// The value is borrowed in-place, i.e. it isn't copied. It stays valid, even
+/// if the value of the handle is replaced, while the guard is alive. The read
+/// access is released when the guard is dropped.
+pub struct Guard<'a, T> {
+    handle: &'a ArcHandle<T>,
+    access: Access<T>,
+}
+
+impl<'a, T> Guard<'a, T> {
+    pub fn version(&self) -> u64 {
+        // SAFETY: See: `Deref::deref` for `Guard`
+        unsafe { Arc::<T>::version_from_raw(self.access.raw_arc_ptr()) }
+    }
+}
+
+impl<'a, T> std::Deref for Guard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The guard holds a read access, i.e. the memory is not freed
+        //   before the guard is dropped.
+        unsafe { Arc::data_ref_from_raw(self.access.raw_arc_ptr()) }
+    }
+}
+
+impl<'a, T> std::Drop for Guard<'a, T> {
+    fn drop(&mut self) {
+        self.handle.release(self.access);
+    }
+}
+
 #[derive(Copy, Clone)]
 #[repr(transparent)]
 pub struct Handle<T> {
@@ -0,0 +1,165 @@
+use crate::reclaim;
+use crate::std;
+
+// Epoch-based reclamation keeps a global epoch, which only ever advances by
+// one. Each thread registers a participant, which it marks as pinned to the
+// global epoch, while it's reading from a shared data structure. The global
+// epoch is only advanced, if every pinned participant is pinned to the current
+// epoch.
+//
+// Memory, which has been unlinked in epoch `e`, may still be read by threads
+// pinned to epoch `e` or earlier. Once the global epoch has advanced to
+// `e + 2`, all of them must have unpinned, because the epoch couldn't have
+// advanced past `e + 1` otherwise. Hence, the memory can be freed then.
+//
+// A thread may pin an outdated epoch, if the global epoch advances between
+// loading and announcing it. That's harmless, because it merely holds back
+// the global epoch until the thread unpins again.
+//
+// Each thread announces its state in a participant of its own, which it
+// acquires from a global registry. See: `reclaim`
+#[cfg(loom)]
+std::lazy_static! {
+    static ref GLOBAL: Global = Global::new();
+}
+
+#[cfg(not(loom))]
+static GLOBAL: Global = Global::new();
+
+std::thread_local! {
+    static LOCAL: Local = Local::new();
+}
+
+/// The state of a participant, which isn't pinned. Otherwise, the state is
+/// the pinned epoch shifted left by one with the lowest bit set.
+const UNPINNED: usize = reclaim::NOTHING;
+
+struct Global {
+    epoch: std::AtomicUsize,
+    participants: reclaim::Registry,
+}
+
+/// The participant of the current thread.
+struct Local {
+    participant: &'static reclaim::Entry,
+    pins: std::Cell<usize>,
+}
+
+/// Keeps the current thread pinned, until it's dropped.
+pub struct Guard {
+    phantom: std::PhantomData<*const Local>,
+}
+
+/// Pins the current thread to the global epoch. Memory unlinked after pinning
+/// isn't freed, until the returned guard has been dropped.
+///
+/// Pinning is reentrant, i.e. the thread stays pinned to the epoch of the
+/// outermost guard.
+pub fn pin() -> Guard {
+    LOCAL.with(|local| {
+        let pins = local.pins.get();
+
+        if pins == 0 {
+            let epoch = global().epoch.load(std::SeqCst);
+
+            local.participant.announce(epoch << 1 | 1);
+        }
+
+        local.pins.set(pins + 1);
+    });
+
+    Guard {
+        phantom: std::PhantomData,
+    }
+}
+
+/// Advances the global epoch, if every pinned participant is pinned to the
+/// current one. Returns the global epoch afterwards.
+pub fn try_advance() -> usize {
+    let global = global();
+    let epoch = global.epoch.load(std::SeqCst);
+
+    for participant in global.participants.entries() {
+        let state = participant.load_latest();
+
+        if state != UNPINNED && state >> 1 != epoch {
+            return epoch;
+        }
+    }
+
+    match global.epoch.compare_exchange(
+        epoch,
+        epoch.wrapping_add(1),
+        std::SeqCst,
+        std::SeqCst,
+    ) {
+        std::Ok(_) => epoch.wrapping_add(1),
+        std::Err(epoch_current) => epoch_current,
+    }
+}
+
+/// Returns `true`, if memory unlinked in epoch `unlinked` can't be read
+/// anymore by threads pinned at the global epoch `current`.
+pub fn is_expired(unlinked: usize, current: usize) -> bool {
+    current.wrapping_sub(unlinked) >= 2
+}
+
+/// Returns the current global epoch.
+pub fn current() -> usize {
+    global().epoch.load(std::SeqCst)
+}
+
+fn global() -> &'static Global {
+    &GLOBAL
+}
+
+impl Global {
+    #[cfg(loom)]
+    fn new() -> Self {
+        let global = Self {
+            epoch: std::AtomicUsize::new(0),
+            participants: reclaim::Registry::new(),
+        };
+
+        std::seqcst_init(&global.epoch);
+
+        global
+    }
+
+    #[cfg(not(loom))]
+    const fn new() -> Self {
+        Self {
+            epoch: std::AtomicUsize::new(0),
+            participants: reclaim::Registry::new(),
+        }
+    }
+}
+
+impl Local {
+    fn new() -> Self {
+        Self {
+            participant: global().participants.acquire(),
+            pins: std::Cell::new(0),
+        }
+    }
+}
+
+impl std::Drop for Local {
+    fn drop(&mut self) {
+        self.participant.release();
+    }
+}
+
+impl std::Drop for Guard {
+    fn drop(&mut self) {
+        LOCAL.with(|local| {
+            let pins = local.pins.get() - 1;
+
+            if pins == 0 {
+                local.participant.announce(UNPINNED);
+            }
+
+            local.pins.set(pins);
+        });
+    }
+}
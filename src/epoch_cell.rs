@@ -0,0 +1,146 @@
+use crate::epoch;
+use crate::reclaim;
+use crate::std;
+
+// The cell points to a heap allocated node holding the current value. Readers
+// pin the current thread, load the pointer and read the value in-place. That's
+// two plain stores to a thread-local participant and two loads of shared
+// memory, none of which are read-modify-write operations.
+//
+// Writers swap in a new node and push the previous one onto a stack of retired
+// nodes, together with the global epoch at that time. Afterwards, they try to
+// advance the global epoch and free all retired nodes of the cell, which can't
+// be read anymore. See: `epoch`
+pub struct EpochCell<T> {
+    current: std::AtomicPtr<Node<T>>,
+    retired: reclaim::Retired<Node<T>>,
+    phantom: std::PhantomData<std::Mutex<T>>,
+}
+
+struct Node<T> {
+    value: T,
+    /// The global epoch at the time the node has been unlinked.
+    epoch: usize,
+    next: *mut Node<T>,
+}
+
+// SAFETY: The cell owns its values, which are moved into the cell by one
+//   thread and borrowed or dropped by another one.
+unsafe impl<T> std::Send for EpochCell<T> where T: std::Send {}
+unsafe impl<T> std::Sync for EpochCell<T> where T: std::Send + std::Sync {}
+
+impl<T> EpochCell<T> {
+    pub fn new(value: T) -> Self {
+        let node = Node::new(value);
+        let cell = Self {
+            current: std::AtomicPtr::new(node),
+            retired: reclaim::Retired::new(),
+            phantom: std::PhantomData,
+        };
+
+        std::seqcst_init(&cell.current);
+
+        cell
+    }
+
+    /// Stores `value` in the cell.
+    ///
+    /// The previous value is dropped by a later call of `set`, once no thread
+    /// can be reading it anymore.
+    pub fn set(&self, value: T) {
+        let previous = self.current.swap(Node::new(value), std::SeqCst);
+
+        // SAFETY: The previous node has been unlinked by the swap.
+        unsafe { self.retire(previous) };
+
+        self.collect();
+    }
+
+    /// Borrows the current value without copying it.
+    ///
+    /// The current thread stays pinned, while the returned guard is alive,
+    /// which delays freeing any value unlinked in the meantime.
+    pub fn load_guard(&self) -> EpochGuard<'_, T> {
+        let pin = epoch::pin();
+
+        // SAFETY: Nodes unlinked after pinning the current thread aren't freed
+        //   before the pin has been released.
+        let value = unsafe { &(*self.current.load(std::SeqCst)).value };
+
+        EpochGuard { value, _pin: pin }
+    }
+
+    /// Pushes an unlinked node onto the stack of retired nodes.
+    ///
+    /// # Safety
+    ///
+    /// The node must have been unlinked and must not be retired twice.
+    unsafe fn retire(&self, node: *mut Node<T>) {
+        // Readers only ever borrow the value of the node.
+        (*node).epoch = epoch::current();
+
+        self.retired.push(node);
+    }
+
+    /// Frees all retired nodes, which can't be read anymore.
+    fn collect(&self) {
+        let epoch = epoch::try_advance();
+
+        self.retired
+            .collect(|node| epoch::is_expired(node.epoch, epoch));
+    }
+}
+
+impl<T> EpochCell<T>
+where
+    T: std::Copy,
+{
+    /// Returns a copy of the current value.
+    ///
+    /// Readers only write to the participant of the current thread, which no
+    /// other thread writes to.
+    pub fn get(&self) -> T {
+        *self.load_guard()
+    }
+}
+
+impl<T> std::Drop for EpochCell<T> {
+    fn drop(&mut self) {
+        // SAFETY: Readers borrow the cell, i.e. no one can be reading the
+        //   current node anymore. Retired nodes are freed by their stack.
+        unsafe {
+            std::drop(std::Box::from_raw(self.current.load(std::SeqCst)));
+        }
+    }
+}
+
+impl<T> Node<T> {
+    fn new(value: T) -> *mut Self {
+        std::Box::into_raw(std::Box::new(Self {
+            value,
+            epoch: 0,
+            next: std::null_mut(),
+        }))
+    }
+}
+
+/// A read access to the value of an `EpochCell`, which keeps the current
+/// thread pinned.
+pub struct EpochGuard<'a, T> {
+    value: &'a T,
+    _pin: epoch::Guard,
+}
+
+impl<'a, T> std::Deref for EpochGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> reclaim::Link for Node<T> {
+    fn next(&mut self) -> &mut *mut Self {
+        &mut self.next
+    }
+}
@@ -0,0 +1,118 @@
+use crate::reclaim;
+use crate::std;
+
+// Every slot holds a hazard pointer, i.e. the address of memory, which a
+// thread is reading. Memory must not be freed, while a slot points to it.
+// Readers announce the pointer they loaded in a slot and load it again. If it
+// hasn't changed, it hasn't been unlinked before the announcement, so writers,
+// which unlink it afterwards, find it when scanning the slots.
+//
+// Each thread keeps a slot for itself. Nested guards acquire additional slots
+// from the global registry. See: `reclaim`
+#[cfg(loom)]
+std::lazy_static! {
+    static ref SLOTS: reclaim::Registry = reclaim::Registry::new();
+}
+
+#[cfg(not(loom))]
+static SLOTS: reclaim::Registry = reclaim::Registry::new();
+
+std::thread_local! {
+    static LOCAL: Local = Local::new();
+}
+
+/// The slot of the current thread.
+struct Local {
+    slot: &'static reclaim::Entry,
+    busy: std::Cell<bool>,
+}
+
+/// Announces, that the current thread is reading the memory a hazard pointer
+/// points to, until it's dropped.
+pub struct Hazard {
+    slot: &'static reclaim::Entry,
+    local: bool,
+    phantom: std::PhantomData<*const Local>,
+}
+
+impl Hazard {
+    /// Acquires the slot of the current thread or, if it's busy, another one.
+    pub fn new() -> Self {
+        let slot = LOCAL.with(|local| {
+            if local.busy.get() {
+                std::None
+            } else {
+                local.busy.set(true);
+                std::Some(local.slot)
+            }
+        });
+
+        Self {
+            slot: slot.unwrap_or_else(|| slots().acquire()),
+            local: slot.is_some(),
+            phantom: std::PhantomData,
+        }
+    }
+
+    /// Loads `pointer` and announces it, until it's stable. The memory it
+    /// points to isn't freed, until the hazard has been cleared or dropped.
+    pub fn protect<T>(&self, pointer: &std::AtomicPtr<T>) -> *mut T {
+        let mut current = pointer.load(std::SeqCst);
+
+        loop {
+            self.slot.announce(current as usize);
+
+            let reloaded = pointer.load(std::SeqCst);
+
+            if reloaded == current {
+                return current;
+            }
+
+            current = reloaded;
+        }
+    }
+}
+
+impl std::Drop for Hazard {
+    fn drop(&mut self) {
+        if self.local {
+            self.slot.announce(reclaim::NOTHING);
+            LOCAL.with(|local| local.busy.set(false));
+        } else {
+            self.slot.release();
+        }
+    }
+}
+
+/// Returns `true`, if any slot points to `pointer`.
+///
+/// The memory must have been unlinked before, so that no reader can announce
+/// it anymore.
+pub fn is_protected<T>(pointer: *const T) -> bool {
+    for slot in slots().entries() {
+        if slot.load_latest() == pointer as usize {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn slots() -> &'static reclaim::Registry {
+    &SLOTS
+}
+
+impl Local {
+    fn new() -> Self {
+        Self {
+            slot: slots().acquire(),
+            busy: std::Cell::new(false),
+        }
+    }
+}
+
+impl std::Drop for Local {
+    fn drop(&mut self) {
+        self.slot.release();
+    }
+}
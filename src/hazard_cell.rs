@@ -0,0 +1,130 @@
+use crate::hazard;
+use crate::reclaim;
+use crate::std;
+
+// The cell points to a heap allocated node holding the current value. Readers
+// announce the node they are reading in a hazard pointer slot and read the
+// value in-place. See: `hazard`
+//
+// Writers swap in a new node and push the previous one onto a stack of retired
+// nodes. Afterwards, they free every retired node of the cell, which no slot
+// points to. A node is only kept, if a reader has announced it, so the number
+// of retired nodes, which haven't been freed, is bounded by the number of
+// guards alive plus the number of concurrent writers. That holds regardless of
+// how long other threads stall.
+pub struct HazardCell<T> {
+    current: std::AtomicPtr<Node<T>>,
+    retired: reclaim::Retired<Node<T>>,
+    phantom: std::PhantomData<std::Mutex<T>>,
+}
+
+struct Node<T> {
+    value: T,
+    next: *mut Node<T>,
+}
+
+// SAFETY: The cell owns its values, which are moved into the cell by one
+//   thread and borrowed or dropped by another one.
+unsafe impl<T> std::Send for HazardCell<T> where T: std::Send {}
+unsafe impl<T> std::Sync for HazardCell<T> where T: std::Send + std::Sync {}
+
+impl<T> HazardCell<T> {
+    pub fn new(value: T) -> Self {
+        let node = Node::new(value);
+        let cell = Self {
+            current: std::AtomicPtr::new(node),
+            retired: reclaim::Retired::new(),
+            phantom: std::PhantomData,
+        };
+
+        std::seqcst_init(&cell.current);
+
+        cell
+    }
+
+    /// Stores `value` in the cell.
+    ///
+    /// The previous value is dropped right away, unless a thread is reading
+    /// it. In that case, it's dropped by a later call of `set`.
+    pub fn set(&self, value: T) {
+        let previous = self.current.swap(Node::new(value), std::SeqCst);
+
+        // SAFETY: The previous node has been unlinked by the swap.
+        unsafe { self.retired.push(previous) };
+
+        self.collect();
+    }
+
+    /// Borrows the current value without copying it.
+    ///
+    /// The value isn't dropped, while the returned guard is alive. Each guard
+    /// keeps at most one replaced value of the cell from being dropped.
+    pub fn load_guard(&self) -> HazardGuard<'_, T> {
+        let hazard = hazard::Hazard::new();
+        let node = hazard.protect(&self.current);
+
+        // SAFETY: The node is protected by the hazard pointer, i.e. it isn't
+        //   freed before the hazard has been dropped.
+        let value = unsafe { &(*node).value };
+
+        HazardGuard {
+            value,
+            _hazard: hazard,
+        }
+    }
+
+    /// Frees all retired nodes, which no thread is reading.
+    fn collect(&self) {
+        self.retired.collect(|node| !hazard::is_protected(node));
+    }
+}
+
+impl<T> HazardCell<T>
+where
+    T: std::Copy,
+{
+    /// Returns a copy of the current value.
+    pub fn get(&self) -> T {
+        *self.load_guard()
+    }
+}
+
+impl<T> std::Drop for HazardCell<T> {
+    fn drop(&mut self) {
+        // SAFETY: Readers borrow the cell, i.e. no one can be reading the
+        //   current node anymore. Retired nodes are freed by their stack.
+        unsafe {
+            std::drop(std::Box::from_raw(self.current.load(std::SeqCst)));
+        }
+    }
+}
+
+impl<T> Node<T> {
+    fn new(value: T) -> *mut Self {
+        std::Box::into_raw(std::Box::new(Self {
+            value,
+            next: std::null_mut(),
+        }))
+    }
+}
+
+/// A read access to the value of a `HazardCell`, which keeps it from being
+/// dropped.
+pub struct HazardGuard<'a, T> {
+    value: &'a T,
+    _hazard: hazard::Hazard,
+}
+
+impl<'a, T> std::Deref for HazardGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> reclaim::Link for Node<T> {
+    fn next(&mut self) -> &mut *mut Self {
+        &mut self.next
+    }
+}
@@ -0,0 +1,231 @@
+use crate::std;
+
+const BITS_PER_BYTE: usize = 8;
+
+// Values, which fit into a native atomic integer, don't need to be allocated
+// at all. They are stored in the low-order bits of an `AtomicU64`. The
+// remaining bits hold the version of the value, which is incremented by every
+// write. Updating the value and the version at once requires at least 32 bits
+// for the version, which is why only values of up to 4 bytes are stored
+// inline. The version wraps around after 2^32 writes for 4 byte values.
+//
+// Values are copied in and out of the atomic variable byte by byte. That's only
+// sound for types without uninitialized bytes and only keeps the meaning of
+// types without interior mutability, whose values are merely copied. Neither
+// can be told from the size, alignment or drop glue of a type, e.g.
+// `MaybeUninit<u32>`, `#[repr(align(4))] struct S(u8)` and `AtomicU32` look
+// just like `u32`. Hence, only the primitive integer types, `bool`, `char` and
+// `f32` are stored inline. They are recognized by their name, because `TypeId`
+// requires `'static` types. A name, which isn't recognized, e.g. because the
+// compiler spells it differently, merely moves the values to the heap.
+pub struct InlineCell<T> {
+    word: std::AtomicU64,
+    phantom: std::PhantomData<T>,
+}
+
+/// The names of the types, which can be stored inline, if they fit. Every
+/// byte of their values is initialized and they are `Copy`.
+const PRIMITIVES: [&str; 11] = [
+    "bool", "char", "f32", "i8", "i16", "i32", "isize", "u8", "u16", "u32",
+    "usize",
+];
+
+impl<T> InlineCell<T> {
+    // Only valid for types, which fit. Hence, the masks are computed where
+    // they're used instead of being constants of their own.
+    const VERSION_SHIFT: usize = std::size_of::<T>() * BITS_PER_BYTE;
+
+    /// Returns `true`, if values of type `T` can be stored in an
+    /// `InlineCell<T>`.
+    pub fn fits() -> bool {
+        let size = std::size_of::<T>();
+
+        (size == 1 || size == 2 || size == 4)
+            && PRIMITIVES.contains(&std::type_name::<T>())
+    }
+
+    /// # Safety
+    ///
+    /// `InlineCell::<T>::fits()` must return `true`.
+    pub unsafe fn new(value: T) -> Self {
+        Self {
+            word: std::AtomicU64::new(Self::bits(&value)),
+            phantom: std::PhantomData,
+        }
+    }
+
+    /// Returns the current version and a bitwise copy of the current value.
+    pub fn load(&self) -> (u64, T) {
+        Self::split(self.word.load(std::SeqCst))
+    }
+
+    pub fn store(&self, value: T) {
+        let bits = Self::bits(&value);
+        let _ = self.fetch_update_bits(|_| std::Some(bits));
+    }
+
+    /// Stores `value` and returns a bitwise copy of the previous value.
+    pub fn swap(&self, value: T) -> T {
+        let bits = Self::bits(&value);
+
+        match self.fetch_update_bits(|_| std::Some(bits)) {
+            std::Ok(word) => Self::split(word).1,
+            // SAFETY: The closure never returns `None`.
+            std::Err(_) => unsafe { std::unreachable_unchecked() },
+        }
+    }
+
+    /// Stores `value`, if the current version is equal to `expected`. Returns
+    /// the new version on success, otherwise `value` is handed back.
+    pub fn store_if_version(
+        &self,
+        expected: u64,
+        value: T,
+    ) -> std::Result<u64, T> {
+        let bits = Self::bits(&value);
+
+        let result = self.fetch_update_bits(|word| {
+            if word >> Self::VERSION_SHIFT == expected {
+                std::Some(bits)
+            } else {
+                std::None
+            }
+        });
+
+        match result {
+            std::Ok(_) => std::Ok(Self::next_version(expected)),
+            std::Err(_) => std::Err(value),
+        }
+    }
+
+    /// Mutably borrows the value in the low-order bits of the word. The value
+    /// is assigned the next version, since it may be changed through the
+    /// borrow.
+    #[cfg(not(loom))]
+    pub fn get_mut(&mut self) -> &mut T {
+        let word = self.word.get_mut();
+        let version = Self::next_version(*word >> Self::VERSION_SHIFT);
+
+        *word = *word & !(u64::MAX << Self::VERSION_SHIFT)
+            | version << Self::VERSION_SHIFT;
+
+        // SAFETY: The low-order bits hold a value of type `T`, which has the
+        //   same size as them and whose alignment is equal to its size. See:
+        //   `InlineCell::fits`
+        unsafe {
+            &mut *((word as *mut u64 as *mut u8).add(Self::offset()) as *mut T)
+        }
+    }
+
+    /// Repeatedly applies `f` to the current word and tries to store the
+    /// returned value bits with the next version, until it succeeds or `f`
+    /// returns `None`. Returns the word `f` has been applied to last.
+    fn fetch_update_bits<F>(&self, mut f: F) -> std::Result<u64, u64>
+    where
+        F: std::FnMut(u64) -> std::Option<u64>,
+    {
+        let mut word = self.word.load(std::SeqCst);
+
+        loop {
+            let bits = match f(word) {
+                std::Some(bits) => bits,
+                std::None => {
+                    return std::Err(word);
+                }
+            };
+
+            let version = Self::next_version(word >> Self::VERSION_SHIFT);
+            let new = bits | version << Self::VERSION_SHIFT;
+
+            let result = self.word.compare_exchange_weak(
+                word,
+                new,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                std::Ok(_) => {
+                    return std::Ok(word);
+                }
+                std::Err(word_current) => {
+                    word = word_current;
+                }
+            }
+        }
+    }
+
+    fn next_version(version: u64) -> u64 {
+        version.wrapping_add(1) & (u64::MAX >> Self::VERSION_SHIFT)
+    }
+
+    /// Returns the bits of `value` as an integer, so the value ends up in the
+    /// low-order bits regardless of the target's endianness.
+    fn bits(value: &T) -> u64 {
+        let mut bits = 0u64;
+
+        // SAFETY: All bytes of `T` are initialized and fit into the integer.
+        //   See: `InlineCell::fits`
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                value as *const T as *const u8,
+                (&mut bits as *mut u64 as *mut u8).add(Self::offset()),
+                std::size_of::<T>(),
+            );
+        }
+
+        bits
+    }
+
+    fn split(word: u64) -> (u64, T) {
+        let bits = word & !(u64::MAX << Self::VERSION_SHIFT);
+        let mut value = std::MaybeUninit::<T>::uninit();
+
+        // SAFETY: The bits have been created from a value of type `T`.
+        let value = unsafe {
+            std::ptr::copy_nonoverlapping(
+                (&bits as *const u64 as *const u8).add(Self::offset()),
+                value.as_mut_ptr() as *mut u8,
+                std::size_of::<T>(),
+            );
+
+            value.assume_init()
+        };
+
+        (word >> Self::VERSION_SHIFT, value)
+    }
+
+    /// Returns the offset of the low-order bytes of a `u64` holding a value.
+    fn offset() -> usize {
+        #[cfg(target_endian = "big")]
+        {
+            std::size_of::<u64>() - std::size_of::<T>()
+        }
+        #[cfg(target_endian = "little")]
+        {
+            0
+        }
+    }
+}
+
+impl<T> InlineCell<T>
+where
+    T: std::Copy,
+{
+    /// Repeatedly applies `f` to the current value and tries to store the
+    /// result, until it succeeds or `f` returns `None`. Returns the value `f`
+    /// has been applied to last.
+    pub fn fetch_update<F>(&self, mut f: F) -> std::Result<T, T>
+    where
+        F: std::FnMut(T) -> std::Option<T>,
+    {
+        let result = self.fetch_update_bits(|word| {
+            f(Self::split(word).1).map(|value| Self::bits(&value))
+        });
+
+        match result {
+            std::Ok(word) => std::Ok(Self::split(word).1),
+            std::Err(word) => std::Err(Self::split(word).1),
+        }
+    }
+}
@@ -6,39 +6,889 @@ mod align128;
 pub mod alloc;
 mod arc;
 mod arc_handle;
+mod epoch;
+mod epoch_cell;
+mod hazard;
+mod hazard_cell;
+mod inline_cell;
+mod pool;
+mod reclaim;
+#[cfg(feature = "serde")]
+mod serde;
+mod snapshot;
 pub mod spsc;
 mod std;
+mod wait_free_cell;
+mod wait_list;
 
 use crate::align128::Align128;
 pub use crate::arc::Arc;
+pub use crate::arc::RawArc;
+pub use crate::arc::Weak;
 use crate::arc_handle::ArcHandle;
+pub use crate::epoch_cell::EpochCell;
+pub use crate::epoch_cell::EpochGuard;
+pub use crate::hazard_cell::HazardCell;
+pub use crate::hazard_cell::HazardGuard;
+use crate::inline_cell::InlineCell;
+use crate::pool::Pool;
+pub use crate::snapshot::publish_all;
+pub use crate::snapshot::snapshot;
+pub use crate::snapshot::Cells;
+pub use crate::wait_free_cell::WaitFreeCell;
+use crate::wait_list::WaitList;
 
-pub struct AtomicCell<T>
-where
-    T: std::Copy,
-{
-    handle: crate::ArcHandle<T>,
+/// A shared, mutable memory location, whose value is read and replaced
+/// atomically.
+///
+/// # Storage
+///
+/// Values are allocated on the heap, so they can be of any size. The memory
+/// of previous values is kept in a pool owned by the cell and reused for new
+/// values.
+///
+/// Small primitive values of 1, 2 or 4 bytes are stored inline in a native
+/// atomic integer together with their version instead. Values of 8 bytes and
+/// more, e.g. `u64` or `u128`, are always allocated. Storing them inline
+/// would take a 128 bit compare-and-swap, which stable Rust doesn't provide,
+/// not even on x86_64. See [`is_inline`][is_inline].
+///
+/// # Thread safety
+///
+/// A cell can be sent to another thread, if `T: Send`. It can be shared
+/// between threads, if `T: Send + Sync`, because any thread may store a
+/// value, which is read or dropped by another one.
+///
+/// ```
+/// fn assert_send_sync<T: Send + Sync>() {}
+///
+/// assert_send_sync::<impatience::AtomicCell<u32>>();
+/// assert_send_sync::<impatience::AtomicCell<String>>();
+/// assert_send_sync::<impatience::AtomicCell<std::sync::Arc<u32>>>();
+/// ```
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+///
+/// assert_send::<impatience::AtomicCell<std::rc::Rc<u32>>>();
+/// ```
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+///
+/// assert_sync::<impatience::AtomicCell<std::cell::Cell<u32>>>();
+/// ```
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+///
+/// assert_sync::<impatience::AtomicCell<std::sync::MutexGuard<'static, u32>>>();
+/// ```
+///
+/// [is_inline]: struct.AtomicCell.html#method.is_inline
+pub struct AtomicCell<T> {
+    storage: Storage<T>,
+    waiters: crate::WaitList,
     phantom: std::PhantomData<std::Mutex<T>>,
 }
 
-// TODO: Auto Trait Implementations
+// SAFETY: The cell owns its value, the inner Arcs and the pool, none of which
+//   are shared with other cells.
+unsafe impl<T> std::Send for AtomicCell<T> where T: std::Send {}
 
-impl<T> AtomicCell<T>
-where
-    T: std::Copy,
-{
+// SAFETY: Values are moved into the cell by one thread and borrowed or dropped
+//   by another one. All accesses to the storage are atomic.
+unsafe impl<T> std::Sync for AtomicCell<T> where T: std::Send + std::Sync {}
+
+// Which field is active only depends on `T`. See: `AtomicCell::is_inline`
+union Storage<T> {
+    inline: std::ManuallyDrop<crate::InlineCell<T>>,
+    shared: std::ManuallyDrop<crate::ArcHandle<T>>,
+}
+
+enum StorageRef<'a, T> {
+    Inline(&'a crate::InlineCell<T>),
+    Shared(&'a crate::ArcHandle<T>),
+}
+
+impl<T> AtomicCell<T> {
     pub fn new(value: T) -> Self {
+        let storage = if Self::is_inline() {
+            Storage {
+                // SAFETY: `T` fits into an `InlineCell<T>`.
+                inline: std::ManuallyDrop::new(unsafe {
+                    crate::InlineCell::new(value)
+                }),
+            }
+        } else {
+            let pool =
+                std::Box::into_raw(std::Box::new(crate::Arc::<T>::pool()));
+
+            Storage {
+                // SAFETY: The pool is freed after the handle has been dropped.
+                //   See: `Drop::drop` for `AtomicCell`
+                shared: std::ManuallyDrop::new(unsafe {
+                    crate::ArcHandle::new(value, pool)
+                }),
+            }
+        };
+
+        Self::from_storage(storage)
+    }
+
+    /// Creates a cell, whose value is initialized in-place by `init`.
+    ///
+    /// Unless the value is [stored inline][is_inline], it's written directly
+    /// into its heap allocation, i.e. it doesn't have to fit on the stack.
+    ///
+    /// # Safety
+    ///
+    /// `init` must initialize the value, unless it panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cell = unsafe {
+    ///     impatience::AtomicCell::<[u64; 1 << 17]>::new_with(|slot| {
+    ///         slot.as_mut_ptr().write_bytes(0, 1);
+    ///     })
+    /// };
+    ///
+    /// unsafe {
+    ///     cell.set_with(|slot| {
+    ///         slot.as_mut_ptr().write_bytes(1, 1);
+    ///     });
+    /// }
+    ///
+    /// assert!(cell.load_guard().iter().all(|&x| x == 0x0101010101010101));
+    /// ```
+    ///
+    /// [is_inline]: struct.AtomicCell.html#method.is_inline
+    pub unsafe fn new_with<F>(init: F) -> Self
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        if Self::is_inline() {
+            return Self::new(Self::init_inline(init));
+        }
+
+        // Returned to the pool, if `init` panics, before the pool is dropped.
+        let pool = std::Box::new(crate::Arc::<T>::pool());
+        let handle = crate::ArcHandle::new_with(init, &*pool);
+
+        // The pool is freed through the pointer held by the handle, after the
+        // handle has been dropped. See: `Drop::drop` for `AtomicCell`
+        std::forget(pool);
+
+        Self::from_storage(Storage {
+            shared: std::ManuallyDrop::new(handle),
+        })
+    }
+
+    fn from_storage(storage: Storage<T>) -> Self {
         Self {
-            handle: crate::ArcHandle::new(value),
+            storage,
+            waiters: crate::WaitList::new(),
             phantom: std::PhantomData,
         }
     }
 
+    /// Initializes a value, which is [stored inline][is_inline], on the stack.
+    ///
+    /// # Safety
+    ///
+    /// `init` must initialize the value, unless it panics.
+    ///
+    /// [is_inline]: struct.AtomicCell.html#method.is_inline
+    unsafe fn init_inline<F>(init: F) -> T
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        let mut value = std::MaybeUninit::uninit();
+
+        init(&mut value);
+
+        value.assume_init()
+    }
+
+    /// Consumes the cell and returns its value.
+    pub fn into_inner(self) -> T {
+        let mut cell = std::ManuallyDrop::new(self);
+
+        // SAFETY: The cell is never used again. No guards borrow from it and
+        //   the pool is freed after the handle has been consumed.
+        unsafe {
+            std::ptr::drop_in_place(&mut cell.waiters);
+
+            if Self::is_inline() {
+                cell.storage.inline.load().1
+            } else {
+                let pool = cell.storage.shared.pool();
+                let handle = std::ManuallyDrop::take(&mut cell.storage.shared);
+                let value = handle.into_unique_inner();
+
+                std::drop(std::Box::from_raw(pool as *mut crate::Pool));
+
+                value
+            }
+        }
+    }
+
+    /// Mutably borrows the value.
+    ///
+    /// No atomic operations are needed, because the cell is borrowed
+    /// exclusively. The value is assigned a new [version][Version], since it
+    /// may be changed through the borrow.
+    ///
+    /// [Version]: struct.Version.html
+    #[cfg(not(loom))]
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: The active field is determined by `is_inline`.
+        unsafe {
+            if Self::is_inline() {
+                (*self.storage.inline).get_mut()
+            } else {
+                (*self.storage.shared).get_mut()
+            }
+        }
+    }
+
+    /// Returns `true`, if values of type `T` are stored inline in a native
+    /// atomic integer instead of being allocated on the heap.
+    ///
+    /// This is the case for the primitive integer types, `bool`, `char` and
+    /// `f32` with a size of 1, 2 or 4 bytes. The remaining bits of the 64 bit
+    /// atomic hold the [version][Version] of the value. Values of 8 bytes
+    /// wouldn't leave any bits for the version and values of 16 bytes don't
+    /// fit, because 128 bit atomics aren't available on stable Rust, even on
+    /// x86_64. Both are allocated.
+    ///
+    /// Other types of the same size, e.g. newtypes, `Option<NonZeroU32>` or
+    /// `AtomicU32`, are allocated, because the cell can't tell, whether all of
+    /// their bytes are initialized and whether they may be copied.
+    ///
+    /// This isn't a `const fn`, because the primitive types are recognized by
+    /// `std::any::type_name`, which can't be called in constants on stable
+    /// Rust.
+    ///
+    /// ```
+    /// use impatience::AtomicCell;
+    /// use std::mem::MaybeUninit;
+    ///
+    /// assert!(AtomicCell::<u32>::is_inline());
+    /// assert!(!AtomicCell::<MaybeUninit<u32>>::is_inline());
+    /// assert!(!AtomicCell::<u64>::is_inline());
+    /// ```
+    ///
+    /// [Version]: struct.Version.html
+    pub fn is_inline() -> bool {
+        crate::InlineCell::<T>::fits()
+    }
+
+    /// Returns `true`, if all operations on the cell are lock-free.
+    ///
+    /// This is the case for all types. Values [stored inline][is_inline] are
+    /// updated together with their version by a single atomic operation.
+    /// Readers of heap allocated values never wait, no matter how many of them
+    /// are reading at the same time. See [`try_get`][try_get] for details.
+    /// Allocating the memory of a new value is up to the global allocator,
+    /// though.
+    ///
+    /// [is_inline]: struct.AtomicCell.html#method.is_inline
+    /// [try_get]: struct.AtomicCell.html#method.try_get
+    pub const fn is_lock_free() -> bool {
+        true
+    }
+
+    /// Stores `value` in the cell.
+    ///
+    /// The previous value is dropped as soon as no one is reading it anymore.
+    /// The value is published with the version following the one of the
+    /// value it replaces.
     pub fn set(&self, value: T) {
-        self.handle.swap(&mut crate::ArcHandle::new(value));
+        std::drop(self.set_keeping_previous(value));
+    }
+
+    /// Stores a value, which is initialized in-place by `init`.
+    ///
+    /// Like [`set`][set], but unless the value is [stored inline][is_inline],
+    /// it's written directly into its heap allocation, i.e. it doesn't have
+    /// to fit on the stack.
+    ///
+    /// # Safety
+    ///
+    /// `init` must initialize the value, unless it panics. The cell isn't
+    /// changed in that case.
+    ///
+    /// [set]: struct.AtomicCell.html#method.set
+    /// [is_inline]: struct.AtomicCell.html#method.is_inline
+    pub unsafe fn set_with<F>(&self, init: F)
+    where
+        F: std::FnOnce(&mut std::MaybeUninit<T>),
+    {
+        match self.storage() {
+            StorageRef::Inline(inline) => inline.store(Self::init_inline(init)),
+            StorageRef::Shared(shared) => {
+                shared.swap(&mut shared.new_sibling_with(init))
+            }
+        }
+
+        self.waiters.notify_all();
+    }
+
+    /// Borrows the current value without copying it.
+    ///
+    /// The returned guard keeps the value alive, even if the cell is updated
+    /// in the meantime. Prefer this over [`get`][get] for large values.
+    ///
+    /// If the value is [stored inline][is_inline], the guard holds a copy of
+    /// it instead.
+    ///
+    /// [get]: struct.AtomicCell.html#method.get
+    /// [is_inline]: struct.AtomicCell.html#method.is_inline
+    pub fn load_guard(&self) -> Guard<'_, T> {
+        let inner = match self.storage() {
+            StorageRef::Inline(inline) => {
+                let (version, value) = inline.load();
+
+                GuardInner::Inline(version, value)
+            }
+            StorageRef::Shared(shared) => {
+                GuardInner::Shared(shared.load_guard())
+            }
+        };
+
+        Guard { inner }
     }
 
+    /// Stores `value` in the cell, if the version of the current value is
+    /// equal to `expected`, i.e. if nothing has been written since `expected`
+    /// has been read.
+    ///
+    /// Returns the version of the stored value on success, otherwise `value`
+    /// is handed back. Unlike [`compare_exchange`][compare_exchange], this
+    /// detects writes of equal values in the meantime.
+    ///
+    /// [compare_exchange]: struct.AtomicCell.html#method.compare_exchange
+    pub fn set_if_version(
+        &self,
+        expected: Version,
+        value: T,
+    ) -> std::Result<Version, T> {
+        let result = match self.storage() {
+            StorageRef::Inline(inline) => {
+                inline.store_if_version(expected.0, value).map(Version)
+            }
+            StorageRef::Shared(shared) => {
+                let mut handle = shared.new_sibling(value);
+
+                if shared.swap_if_version(expected.0, &mut handle) {
+                    std::Ok(Version(expected.0.wrapping_add(1)))
+                } else {
+                    std::Err(handle.into_unpublished_inner())
+                }
+            }
+        };
+
+        if result.is_ok() {
+            self.waiters.notify_all();
+        }
+
+        result
+    }
+
+    /// Blocks the current thread until a value with a version other than
+    /// `since` has been stored and returns the version of the current value.
+    ///
+    /// Returns right away, if the version of the current value differs from
+    /// `since` already. Writers only pay for waking up threads, if there are
+    /// any waiting.
+    pub fn wait_changed(&self, since: Version) -> Version {
+        let mut version = since;
+
+        self.waiters.wait_until(|| {
+            version = self.version();
+            version != since
+        });
+
+        version
+    }
+
+    /// Like [`wait_changed`][wait_changed], but gives up after `timeout` has
+    /// elapsed. Returns `None`, if the version is still equal to `since`.
+    ///
+    /// [wait_changed]: struct.AtomicCell.html#method.wait_changed
+    #[cfg(not(loom))]
+    pub fn wait_changed_timeout(
+        &self,
+        since: Version,
+        timeout: std::Duration,
+    ) -> std::Option<Version> {
+        let mut version = since;
+
+        let changed = self.waiters.wait_until_timeout(
+            || {
+                version = self.version();
+                version != since
+            },
+            timeout,
+        );
+
+        if changed {
+            std::Some(version)
+        } else {
+            std::None
+        }
+    }
+
+    /// Returns a future, which resolves to the version of the current value
+    /// once a value has been stored after calling this method.
+    ///
+    /// The future doesn't depend on any particular executor. Create it before
+    /// reading the value to not miss any writes in between, or use
+    /// [`changed_since`][changed_since] with the version of the value read.
+    ///
+    /// [changed_since]: struct.AtomicCell.html#method.changed_since
+    pub fn changed(&self) -> Changed<'_, T> {
+        self.changed_since(self.version())
+    }
+
+    /// Returns a future, which resolves to the version of the current value
+    /// once its version differs from `since`.
+    ///
+    /// See [`wait_changed`][wait_changed] for the blocking equivalent.
+    ///
+    /// [wait_changed]: struct.AtomicCell.html#method.wait_changed
+    pub fn changed_since(&self, since: Version) -> Changed<'_, T> {
+        Changed {
+            cell: self,
+            since,
+            registration: std::None,
+        }
+    }
+
+    /// Returns a clone of the current value.
+    pub fn get_cloned(&self) -> T
+    where
+        T: std::Clone,
+    {
+        (*self.load_guard()).clone()
+    }
+}
+
+impl<T> AtomicCell<T>
+where
+    T: std::Clone,
+{
+    /// Stores `value` in the cell and returns the previous value.
+    ///
+    /// The previous value is moved out, unless guards or readers still
+    /// borrow it. It's cloned in that case.
+    pub fn swap(&self, value: T) -> T {
+        let previous = match self.storage() {
+            // Guards may hold copies of the previous value.
+            StorageRef::Inline(inline) => {
+                let previous = std::ManuallyDrop::new(inline.swap(value));
+
+                (*previous).clone()
+            }
+            StorageRef::Shared(shared) => {
+                let mut handle = shared.new_sibling(value);
+                shared.swap(&mut handle);
+
+                handle.into_inner_or_clone()
+            }
+        };
+
+        self.waiters.notify_all();
+
+        previous
+    }
+
+    /// Replaces the contained value with `value` and returns the previous
+    /// value.
+    ///
+    /// This is an alias for [`swap`][swap].
+    ///
+    /// [swap]: struct.AtomicCell.html#method.swap
+    pub fn replace(&self, value: T) -> T {
+        self.swap(value)
+    }
+}
+
+impl<T> AtomicCell<T>
+where
+    T: std::Default,
+{
+    /// Replaces the value with its default and returns the previous value.
+    ///
+    /// `T` must be `Clone`, because guards or readers may still borrow the
+    /// previous value. It can't be moved out in that case, so it's cloned.
+    /// See [`swap`][swap].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cell = impatience::AtomicCell::new(vec![1u8, 2, 3]);
+    ///
+    /// assert_eq!(cell.take(), [1, 2, 3]);
+    /// assert!(cell.load_guard().is_empty());
+    /// ```
+    ///
+    /// [swap]: struct.AtomicCell.html#method.swap
+    pub fn take(&self) -> T
+    where
+        T: std::Clone,
+    {
+        self.swap(T::default())
+    }
+}
+
+impl<T> AtomicCell<T>
+where
+    T: std::Copy,
+{
     pub fn get(&self) -> T {
-        self.handle.get()
+        match self.storage() {
+            StorageRef::Inline(inline) => inline.load().1,
+            StorageRef::Shared(shared) => shared.get(),
+        }
+    }
+
+    /// Returns a copy of the current value and its version.
+    ///
+    /// See [`set_if_version`][set_if_version].
+    ///
+    /// [set_if_version]: struct.AtomicCell.html#method.set_if_version
+    pub fn get_versioned(&self) -> (Version, T) {
+        let (version, value) = match self.storage() {
+            StorageRef::Inline(inline) => inline.load(),
+            StorageRef::Shared(shared) => shared.get_versioned(),
+        };
+
+        (Version(version), value)
+    }
+
+    /// Returns a copy of the current value, unless the maximum number of
+    /// readers registered at the same time has been reached.
+    ///
+    /// Up to 127 readers, including guards, can be registered with a cell at
+    /// the same time. [`get`][get] acquires a reference to the value instead,
+    /// if there are more, which takes another atomic operation on the value's
+    /// reference count. This method fails instead. It always succeeds for
+    /// values [stored inline][is_inline].
+    ///
+    /// [get]: struct.AtomicCell.html#method.get
+    /// [is_inline]: struct.AtomicCell.html#method.is_inline
+    pub fn try_get(&self) -> std::Result<T, ReaderSaturated> {
+        match self.storage() {
+            StorageRef::Inline(inline) => std::Ok(inline.load().1),
+            StorageRef::Shared(shared) => {
+                shared.try_get().ok_or(ReaderSaturated)
+            }
+        }
+    }
+
+    /// Fetches the value and applies `f` to it, which returns an optional new
+    /// value. Returns `Ok(previous_value)`, if the new value has been stored,
+    /// otherwise `Err(previous_value)`.
+    ///
+    /// `f` may be called multiple times, if the value has been changed by
+    /// another thread in the meantime, but the new value is only stored, if
+    /// it has been computed from the value it replaces.
+    pub fn fetch_update<F>(&self, f: F) -> std::Result<T, T>
+    where
+        F: std::FnMut(T) -> std::Option<T>,
+    {
+        let result = match self.storage() {
+            StorageRef::Inline(inline) => inline.fetch_update(f),
+            StorageRef::Shared(shared) => shared.fetch_update(f),
+        };
+
+        if result.is_ok() {
+            self.waiters.notify_all();
+        }
+
+        result
+    }
+
+    /// Replaces the value with the result of `f` applied to it and returns the
+    /// previous value.
+    ///
+    /// See [`fetch_update`][fetch_update] for details.
+    ///
+    /// [fetch_update]: struct.AtomicCell.html#method.fetch_update
+    pub fn update<F>(&self, mut f: F) -> T
+    where
+        F: std::FnMut(T) -> T,
+    {
+        match self.fetch_update(|value| std::Some(f(value))) {
+            std::Ok(value) => value,
+            // SAFETY: The closure never returns `None`.
+            std::Err(_) => unsafe { std::unreachable_unchecked() },
+        }
+    }
+}
+
+impl<T> AtomicCell<T>
+where
+    T: std::Copy + std::Eq,
+{
+    /// Stores `new` in the cell, if the current value is equal to `current`.
+    ///
+    /// The return value is a result indicating whether the new value has been
+    /// written and containing the previous value. On success this value is
+    /// guaranteed to be equal to `current`.
+    ///
+    /// The comparison is done on the values, not on their memory
+    /// representation.
+    pub fn compare_exchange(&self, current: T, new: T) -> std::Result<T, T> {
+        let result = match self.storage() {
+            StorageRef::Inline(inline) => inline.fetch_update(|value| {
+                if value == current {
+                    std::Some(new)
+                } else {
+                    std::None
+                }
+            }),
+            StorageRef::Shared(shared) => {
+                shared.compare_exchange(current, &mut shared.new_sibling(new))
+            }
+        };
+
+        if result.is_ok() {
+            self.waiters.notify_all();
+        }
+
+        result
+    }
+}
+
+impl<T> AtomicCell<T> {
+    /// Like `set`, but returns the handle of the previous value instead of
+    /// dropping it. Inline values don't need to be dropped.
+    fn set_keeping_previous(&self, value: T) -> std::Option<ArcHandle<T>> {
+        let previous = match self.storage() {
+            StorageRef::Inline(inline) => {
+                inline.store(value);
+
+                std::None
+            }
+            StorageRef::Shared(shared) => {
+                let mut handle = shared.new_sibling(value);
+                shared.swap(&mut handle);
+
+                std::Some(handle)
+            }
+        };
+
+        self.waiters.notify_all();
+
+        previous
+    }
+
+    fn version(&self) -> Version {
+        match self.storage() {
+            StorageRef::Inline(inline) => Version(inline.load().0),
+            StorageRef::Shared(shared) => Version(shared.version()),
+        }
+    }
+
+    fn storage(&self) -> StorageRef<'_, T> {
+        // SAFETY: The active field is determined by `is_inline`.
+        unsafe {
+            if Self::is_inline() {
+                StorageRef::Inline(&self.storage.inline)
+            } else {
+                StorageRef::Shared(&self.storage.shared)
+            }
+        }
+    }
+}
+
+impl<T> std::Drop for AtomicCell<T> {
+    fn drop(&mut self) {
+        // Inline values don't need to be dropped. See: `InlineCell::fits`
+        if !Self::is_inline() {
+            // SAFETY: The shared field is active and never used again. The
+            //   pool has been allocated by `AtomicCell::new` and the handle
+            //   has been the last one allocating from it.
+            unsafe {
+                let pool = self.storage.shared.pool();
+
+                std::ManuallyDrop::drop(&mut self.storage.shared);
+                std::drop(std::Box::from_raw(pool as *mut crate::Pool));
+            }
+        }
+    }
+}
+
+impl<T> std::Clone for AtomicCell<T>
+where
+    T: std::Clone,
+{
+    /// Creates a new cell with a clone of the current value.
+    fn clone(&self) -> Self {
+        Self::new(self.get_cloned())
     }
 }
+
+impl<T> std::fmt::Debug for AtomicCell<T>
+where
+    T: std::fmt::Debug,
+{
+    /// Formats a snapshot of the current value.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AtomicCell")
+            .field("value", &*self.load_guard())
+            .finish()
+    }
+}
+
+impl<T> std::Default for AtomicCell<T>
+where
+    T: std::Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> std::From<T> for AtomicCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+/// A read access to the value of an `AtomicCell`.
+///
+/// The value is borrowed in-place, i.e. it isn't copied. It stays valid, even
+/// if the value of the cell is replaced, while the guard is alive. The read
+/// access is released when the guard is dropped.
+///
+/// Values [stored inline][is_inline] are copied into the guard instead.
+///
+/// [is_inline]: struct.AtomicCell.html#method.is_inline
+pub struct Guard<'a, T> {
+    inner: GuardInner<'a, T>,
+}
+
+enum GuardInner<'a, T> {
+    Inline(u64, T),
+    Shared(crate::arc_handle::Guard<'a, T>),
+}
+
+impl<'a, T> Guard<'a, T> {
+    /// Returns the version of the borrowed value.
+    pub fn version(&self) -> Version {
+        match &self.inner {
+            GuardInner::Inline(version, _) => Version(*version),
+            GuardInner::Shared(guard) => Version(guard.version()),
+        }
+    }
+}
+
+impl<'a, T> std::Deref for Guard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.inner {
+            GuardInner::Inline(_, value) => value,
+            GuardInner::Shared(guard) => guard,
+        }
+    }
+}
+
+impl<'a, T> std::fmt::Debug for Guard<'a, T>
+where
+    T: std::fmt::Debug,
+{
+    /// Formats the borrowed value and its version.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Guard")
+            .field("version", &self.version())
+            .field("value", &**self)
+            .finish()
+    }
+}
+
+/// A future returned by [`AtomicCell::changed`][changed], which resolves to
+/// the version of the value stored after the future has been created.
+///
+/// The task is woken up by the next write to the cell, i.e. there's no window
+/// in which a write may go unnoticed.
+///
+/// [changed]: struct.AtomicCell.html#method.changed
+pub struct Changed<'a, T> {
+    cell: &'a AtomicCell<T>,
+    since: Version,
+    registration: std::Option<crate::wait_list::Registration<'a>>,
+}
+
+impl<'a, T> std::Future for Changed<'a, T> {
+    type Output = Version;
+
+    fn poll(
+        self: std::Pin<&mut Self>,
+        cx: &mut std::Context<'_>,
+    ) -> std::Poll<Self::Output> {
+        let this = self.get_mut();
+        let version = this.cell.version();
+
+        if version != this.since {
+            return std::Poll::Ready(version);
+        }
+
+        if let std::Some(registration) = &this.registration {
+            if !registration.is_notified() && registration.will_wake(cx.waker())
+            {
+                return std::Poll::Pending;
+            }
+        }
+
+        // The previous registration, if any, is dropped only after the new
+        // one has been pushed, so there's no gap without a registration.
+        this.registration = std::Some(
+            this.cell.waiters.register(std::Clone::clone(cx.waker())),
+        );
+
+        let version = this.cell.version();
+
+        if version != this.since {
+            std::Poll::Ready(version)
+        } else {
+            std::Poll::Pending
+        }
+    }
+}
+
+/// The version of a value stored in an `AtomicCell`.
+///
+/// The initial value of a cell has the version 0. Every write publishes its
+/// value with the version following the one of the value it replaces, so two
+/// values of the same cell never share a version, even if they are equal.
+///
+/// Values [stored inline][is_inline] have fewer bits for their version, i.e.
+/// the version wraps around after 2^32 writes for 4 byte values, after 2^48
+/// writes for 2 byte values and so on. A version read that many writes ago is
+/// equal to the current one again. Values of 8 bytes and more are allocated,
+/// i.e. their version doesn't wrap around in practice.
+///
+/// [is_inline]: struct.AtomicCell.html#method.is_inline
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Version(u64);
+
+/// The error returned by [`AtomicCell::try_get`][try_get], if too many readers
+/// are registered at the same time.
+///
+/// [try_get]: struct.AtomicCell.html#method.try_get
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ReaderSaturated;
+
+impl std::fmt::Display for ReaderSaturated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("too many readers are registered at the same time")
+    }
+}
+
+impl std::Error for ReaderSaturated {}
@@ -0,0 +1,149 @@
+use crate::std;
+
+// Every update of an `AtomicCell` allocates a new `ArcInner`, which is freed
+// once the last reader is done with it. A pool keeps those blocks around after
+// their data has been dropped and hands them out to the next writer, so
+// updating a cell doesn't hit the global allocator in the steady state.
+//
+// Blocks are never returned to the global allocator before the pool is
+// dropped. Readers, which find the read count of a handle saturated, increment
+// the reference count of an `ArcInner`, which may have been freed in the
+// meantime. That's only sound, because the memory stays allocated and the
+// count is only ever accessed atomically. See: `ArcHandle::acquire_counted`
+// Hence, a pool holds as many blocks as values of its cell have been alive at
+// the same time.
+//
+// Free blocks form a linked stack. A block is put back by pushing it and taken
+// out by swapping in a null pointer, i.e. by taking the whole stack at once.
+// Unlike popping a single block, this doesn't suffer from the ABA problem. The
+// first block is kept and the rest of the stack is pushed back. Links are kept
+// within the blocks at an offset, which the owner of the pool doesn't access
+// while a block is free.
+//
+// A pool must outlive all blocks allocated from it. It is owned by the
+// `AtomicCell` and dropped after the cell's value.
+pub struct Pool {
+    free: std::AtomicPtr<u8>,
+    layout: std::Layout,
+    /// The offset of the link to the next free block within a block.
+    link_offset: usize,
+}
+
+impl Pool {
+    /// Creates an empty pool of blocks with the given layout, which keeps the
+    /// links between free blocks at `link_offset`.
+    pub fn new(layout: std::Layout, link_offset: usize) -> Self {
+        Self {
+            free: std::AtomicPtr::new(std::null_mut()),
+            layout,
+            link_offset,
+        }
+    }
+
+    /// Takes a free block out of the pool. Returns a null pointer, if the pool
+    /// is empty.
+    pub fn take(&self) -> *mut u8 {
+        let block = self.free.swap(std::null_mut(), std::SeqCst);
+
+        if !block.is_null() {
+            // SAFETY: The stack has been taken out of the pool, i.e. no one
+            //   else accesses the links of its blocks.
+            unsafe {
+                let rest = self.next(block);
+
+                if !rest.is_null() {
+                    self.push(rest);
+                }
+            }
+        }
+
+        block
+    }
+
+    /// Allocates a new block, which is put into the pool by `dealloc`.
+    pub fn alloc(&self) -> *mut u8 {
+        let block = unsafe { std::alloc(self.layout) };
+
+        if block.is_null() {
+            std::handle_alloc_error(self.layout);
+        }
+
+        block
+    }
+
+    /// Puts a block back into the pool.
+    ///
+    /// # Safety
+    ///
+    /// The block must have been returned by `take` or `alloc` of the same pool
+    /// and must not be used afterwards, apart from accessing memory, which
+    /// doesn't overlap with the link, atomically.
+    pub unsafe fn dealloc(&self, block: *mut u8) {
+        self.set_next(block, std::null_mut());
+        self.push(block);
+    }
+
+    /// Pushes a stack of free blocks, which is terminated by a null pointer.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own all blocks of the stack.
+    unsafe fn push(&self, first: *mut u8) {
+        let mut last: *mut u8 = std::null_mut();
+        let mut head = std::null_mut();
+
+        loop {
+            let result = self.free.compare_exchange(
+                head,
+                first,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                std::Ok(_) => {
+                    return;
+                }
+                std::Err(head_current) => {
+                    // Blocks have been pushed in the meantime. They're linked
+                    // below the last block of the stack.
+                    if last.is_null() {
+                        last = first;
+
+                        while !self.next(last).is_null() {
+                            last = self.next(last);
+                        }
+                    }
+
+                    self.set_next(last, head_current);
+                    head = head_current;
+                }
+            }
+        }
+    }
+
+    unsafe fn next(&self, block: *mut u8) -> *mut u8 {
+        *(block.add(self.link_offset) as *const *mut u8)
+    }
+
+    unsafe fn set_next(&self, block: *mut u8, next: *mut u8) {
+        *(block.add(self.link_offset) as *mut *mut u8) = next;
+    }
+}
+
+impl std::Drop for Pool {
+    fn drop(&mut self) {
+        let mut block = self.free.swap(std::null_mut(), std::SeqCst);
+
+        while !block.is_null() {
+            // SAFETY: The block has been allocated with `self.layout` and
+            //   nobody else has access to the pool anymore.
+            unsafe {
+                let next = self.next(block);
+
+                std::dealloc(block, self.layout);
+                block = next;
+            }
+        }
+    }
+}
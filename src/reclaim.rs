@@ -0,0 +1,265 @@
+use crate::std;
+
+// Hazard pointers and epochs share the same building blocks: a global registry
+// of entries, in which threads announce what they're reading, and a stack of
+// retired nodes per cell, which are freed once no announcement refers to them.
+//
+// Entries are never freed. A thread releases its entry when it exits and the
+// next thread acquiring one reuses it. Each entry is padded to a cache line of
+// its own, because its owner writes to it on every read, while writers scan
+// all of them.
+//
+// Collecting takes the whole stack of retired nodes at once by swapping in a
+// null pointer and pushes the nodes, which can't be freed, yet, back one by
+// one. That rules out the ABA problem of popping single nodes.
+
+/// The announcement of an entry, which isn't used for reading.
+pub const NOTHING: usize = 0;
+
+/// A value, which is never announced, i.e. neither an epoch state nor the
+/// address of a node. See: `Entry::load_latest`
+#[cfg(loom)]
+const NEVER_ANNOUNCED: usize = 2;
+
+pub struct Registry {
+    head: std::AtomicPtr<crate::Align128<Entry>>,
+}
+
+pub struct Entry {
+    announcement: std::AtomicUsize,
+    in_use: std::AtomicBool,
+    next: *mut crate::Align128<Entry>,
+}
+
+/// Iterates over all entries of a registry, including released ones.
+pub struct Entries {
+    entry: *mut crate::Align128<Entry>,
+}
+
+/// A node, which can be pushed onto a stack of retired nodes.
+pub trait Link {
+    /// Returns the pointer to the next node on the stack.
+    fn next(&mut self) -> &mut *mut Self;
+}
+
+/// A stack of nodes, which have been unlinked, but may still be read.
+pub struct Retired<N>
+where
+    N: Link,
+{
+    head: std::AtomicPtr<N>,
+    phantom: std::PhantomData<std::Box<N>>,
+}
+
+impl Registry {
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        let registry = Self {
+            head: std::AtomicPtr::new(std::null_mut()),
+        };
+
+        std::seqcst_init(&registry.head);
+
+        registry
+    }
+
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        Self {
+            head: std::AtomicPtr::new(std::null_mut()),
+        }
+    }
+
+    /// Reuses a released entry or registers a new one.
+    pub fn acquire(&self) -> &'static Entry {
+        for entry in self.entries() {
+            let result = entry.in_use.compare_exchange(
+                false,
+                true,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            if result.is_ok() {
+                return entry;
+            }
+        }
+
+        let entry = std::Box::into_raw(std::Box::new(crate::Align128(Entry {
+            announcement: std::AtomicUsize::new(NOTHING),
+            in_use: std::AtomicBool::new(true),
+            next: std::null_mut(),
+        })));
+        let mut head = self.head.load(std::SeqCst);
+
+        loop {
+            // SAFETY: The entry hasn't been published, yet.
+            unsafe { (*entry).0.next = head };
+
+            let result = self.head.compare_exchange(
+                head,
+                entry,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                // SAFETY: Entries are never freed.
+                std::Ok(_) => {
+                    return unsafe { &*entry };
+                }
+                std::Err(head_current) => {
+                    head = head_current;
+                }
+            }
+        }
+    }
+
+    pub fn entries(&self) -> Entries {
+        Entries {
+            entry: self.head.load(std::SeqCst),
+        }
+    }
+}
+
+impl Entry {
+    pub fn announce(&self, announcement: usize) {
+        self.announcement.store(announcement, std::SeqCst);
+    }
+
+    /// Loads the announcement after unlinking memory.
+    pub fn load_latest(&self) -> usize {
+        // loom doesn't model the single total order of `SeqCst` operations on
+        // different atomic variables, i.e. the load may miss a thread, which
+        // announced itself before loading the unlinked memory. A
+        // read-modify-write operation always reads the latest announcement.
+        // It must not succeed, though, because loom doesn't order its store
+        // after the announcing one.
+        #[cfg(loom)]
+        {
+            match self.announcement.compare_exchange(
+                NEVER_ANNOUNCED,
+                NEVER_ANNOUNCED,
+                std::SeqCst,
+                std::SeqCst,
+            ) {
+                std::Ok(announcement) | std::Err(announcement) => announcement,
+            }
+        }
+        #[cfg(not(loom))]
+        {
+            self.announcement.load(std::SeqCst)
+        }
+    }
+
+    /// Clears the announcement and hands the entry over to the next thread
+    /// acquiring one.
+    pub fn release(&self) {
+        self.announce(NOTHING);
+        self.in_use.store(false, std::SeqCst);
+    }
+}
+
+// SAFETY: The successor is never changed after the entry has been published.
+//   All other fields are atomic.
+unsafe impl std::Send for Entry {}
+unsafe impl std::Sync for Entry {}
+
+impl std::Iterator for Entries {
+    type Item = &'static Entry;
+
+    fn next(&mut self) -> std::Option<Self::Item> {
+        if self.entry.is_null() {
+            return std::None;
+        }
+
+        // SAFETY: Entries are never freed and their successor never changes.
+        let entry = unsafe { &*self.entry };
+        self.entry = entry.next;
+
+        std::Some(entry)
+    }
+}
+
+impl<N> Retired<N>
+where
+    N: Link,
+{
+    pub fn new() -> Self {
+        Self {
+            head: std::AtomicPtr::new(std::null_mut()),
+            phantom: std::PhantomData,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The node must have been allocated by `Box`, must have been unlinked
+    /// and must not be on the stack already.
+    pub unsafe fn push(&self, node: *mut N) {
+        let mut head = self.head.load(std::SeqCst);
+
+        loop {
+            *(*node).next() = head;
+
+            let result = self.head.compare_exchange(
+                head,
+                node,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                std::Ok(_) => {
+                    return;
+                }
+                std::Err(head_current) => {
+                    head = head_current;
+                }
+            }
+        }
+    }
+
+    /// Frees all nodes, for which `is_unreachable` returns `true`.
+    pub fn collect<F>(&self, mut is_unreachable: F)
+    where
+        F: std::FnMut(&N) -> bool,
+    {
+        let mut node = self.head.swap(std::null_mut(), std::SeqCst);
+
+        while !node.is_null() {
+            // SAFETY: The nodes have been taken off the stack, i.e. no other
+            //   writer has access to them.
+            unsafe {
+                let next = *(*node).next();
+
+                if is_unreachable(&*node) {
+                    std::drop(std::Box::from_raw(node));
+                } else {
+                    self.push(node);
+                }
+
+                node = next;
+            }
+        }
+    }
+}
+
+impl<N> std::Drop for Retired<N>
+where
+    N: Link,
+{
+    fn drop(&mut self) {
+        let mut node = self.head.load(std::SeqCst);
+
+        // SAFETY: The stack is owned by a cell, which isn't borrowed by any
+        //   reader anymore.
+        unsafe {
+            while !node.is_null() {
+                let next = *(*node).next();
+                std::drop(std::Box::from_raw(node));
+                node = next;
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::std;
+
+// Cells are serialized transparently as their value, like `Mutex` and
+// `RefCell` are by serde itself. Deserializing creates a new cell.
+
+/// Serializes a snapshot of the current value. The value is borrowed by a
+/// guard, i.e. it isn't copied and it stays valid, even if the cell is
+/// updated while being serialized.
+impl<T> ::serde::Serialize for crate::AtomicCell<T>
+where
+    T: ::serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        ::serde::Serialize::serialize(&*self.load_guard(), serializer)
+    }
+}
+
+impl<'de, T> ::serde::Deserialize<'de> for crate::AtomicCell<T>
+where
+    T: ::serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Serializes the value, which hasn't been taken by a consumer, yet. Fails, if
+/// the value has been taken already.
+impl<T> ::serde::Serialize for crate::spsc::Cell<T>
+where
+    T: ::serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        match self.pending() {
+            std::Some(value) => {
+                ::serde::Serialize::serialize(value, serializer)
+            }
+            std::None => std::Err(::serde::ser::Error::custom(
+                "the value has been taken by a consumer",
+            )),
+        }
+    }
+}
+
+impl<'de, T> ::serde::Deserialize<'de> for crate::spsc::Cell<T>
+where
+    T: ::serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
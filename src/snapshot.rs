@@ -0,0 +1,308 @@
+use crate::std;
+
+// A snapshot reads every cell once and then checks, that none of the versions
+// has changed since. If so, each value has been current from the moment it
+// has been read until its version has been checked again, i.e. all of them
+// have been current at the moment the first version has been checked again.
+// Otherwise, the snapshot is retried.
+//
+// That alone would allow a snapshot to observe some of the values written by
+// `publish_all`, but not the others. Hence, `publish_all` makes a sequence
+// number odd, while it's writing, and even again, when it's done. A snapshot
+// only succeeds, if the sequence number has been even and unchanged from
+// before the first read until after the last check.
+//
+// Each cell maps to one of `STRIPES` sequence numbers by its address, so
+// `publish_all` only serializes writers and delays snapshots of cells, which
+// share a stripe with one of the cells it writes. Stripes are locked in
+// ascending order, which rules out deadlocks between writers of overlapping
+// groups of cells. Neither affects readers or writers of single cells.
+//
+// Plain writes of single cells can't be ordered with respect to each other
+// anyway, so they only need to be covered by the version check.
+const STRIPES: usize = 64;
+
+type Sequences = [crate::Align128<std::AtomicUsize>; STRIPES];
+
+#[cfg(loom)]
+std::lazy_static! {
+    static ref SEQUENCES: Sequences = new_sequences();
+}
+
+/// The initial value of each sequence number. Array repeat expressions copy a
+/// constant, even if its type isn't `Copy`.
+// Each use of the constant is a new atomic, which is the intent here. It's
+// only used to initialize `SEQUENCES`.
+#[cfg(not(loom))]
+#[allow(clippy::declare_interior_mutable_const)]
+const SEQUENCE: crate::Align128<std::AtomicUsize> =
+    crate::Align128(std::AtomicUsize::new(0));
+
+#[cfg(not(loom))]
+static SEQUENCES: Sequences = [SEQUENCE; STRIPES];
+
+/// A tuple of references to `AtomicCell`s, which can be read by
+/// [`snapshot`][snapshot] and written by [`publish_all`][publish_all].
+///
+/// Implemented for tuples of up to eight references. The trait is sealed,
+/// i.e. it can't be implemented outside of this crate, and its methods can
+/// only be used through `snapshot` and `publish_all`.
+///
+/// [snapshot]: fn.snapshot.html
+/// [publish_all]: fn.publish_all.html
+pub trait Cells<'a>: UnlockedCells<'a> {}
+
+/// The operations on a tuple of cells, which `snapshot` and `publish_all`
+/// wrap in the sequence number protocol. Private, so they can't be used
+/// without it.
+pub trait UnlockedCells<'a>: std::Copy {
+    /// A tuple of guards, one for each cell.
+    type Guards;
+
+    /// A tuple of values, one for each cell.
+    type Values;
+
+    /// A tuple of the previous values returned by `set_all`, one for each
+    /// cell.
+    type Previous;
+
+    /// Borrows the current value of each cell.
+    fn load_guards(self) -> Self::Guards;
+
+    /// Returns `true`, if the value of each cell still has the version of
+    /// the corresponding guard.
+    fn is_current(self, guards: &Self::Guards) -> bool;
+
+    /// Stores each value in the corresponding cell and returns the previous
+    /// values, which may be dropped once the stripes have been released.
+    fn set_all(self, values: Self::Values) -> Self::Previous;
+
+    /// Returns the set of sequence number stripes the cells map to, one bit
+    /// per stripe.
+    fn stripes(self) -> u64;
+}
+
+/// Borrows the values of several cells, which have all been current at the
+/// same instant.
+///
+/// No snapshot observes some but not all of the values written by a call of
+/// [`publish_all`][publish_all]. Snapshots are retried, while the cells are
+/// written, and wait for calls of `publish_all` writing any of the cells to
+/// finish. Unrelated cells may occasionally share a sequence number with one
+/// of the cells, in which case their writers are waited for, too.
+///
+/// # Examples
+///
+/// ```
+/// let a = impatience::AtomicCell::new(1u32);
+/// let b = impatience::AtomicCell::new(String::from("one"));
+///
+/// impatience::publish_all((&a, &b), (2, String::from("two")));
+///
+/// let (a, b) = impatience::snapshot((&a, &b));
+///
+/// assert_eq!((*a, b.as_str()), (2, "two"));
+/// ```
+///
+/// [publish_all]: fn.publish_all.html
+pub fn snapshot<'a, C>(cells: C) -> C::Guards
+where
+    C: Cells<'a>,
+{
+    let stripes = cells.stripes();
+
+    loop {
+        if let std::Some(before) = sum_sequences(stripes) {
+            let guards = cells.load_guards();
+
+            if cells.is_current(&guards)
+                && sum_sequences(stripes) == std::Some(before)
+            {
+                return guards;
+            }
+        }
+
+        std::yield_now();
+    }
+}
+
+/// Stores each value in the corresponding cell, so that no
+/// [`snapshot`][snapshot] observes some but not all of them.
+///
+/// Calls of `publish_all` block each other, if they write overlapping groups
+/// of cells. Single reads and writes of the cells are not affected, i.e. they
+/// may observe the values being stored one after the other.
+///
+/// The previous values are dropped after all of the new values have been
+/// published, so their `Drop` implementations may use `snapshot` and
+/// `publish_all`, too.
+///
+/// [snapshot]: fn.snapshot.html
+pub fn publish_all<'a, C>(cells: C, values: C::Values)
+where
+    C: Cells<'a>,
+{
+    let stripes = cells.stripes();
+
+    for stripe in Stripes(stripes) {
+        lock_sequence(&get_sequences()[stripe]);
+    }
+
+    let previous = {
+        // Storing the values may panic. Snapshots must not wait forever
+        // nevertheless.
+        let _publishing = Publishing { stripes };
+
+        cells.set_all(values)
+    };
+
+    // The previous values are dropped after the stripes have been released,
+    // in case dropping them takes snapshots or publishes values itself.
+    std::drop(previous);
+}
+
+/// Makes the sequence numbers of the stripes even again, when dropped.
+struct Publishing {
+    stripes: u64,
+}
+
+impl std::Drop for Publishing {
+    fn drop(&mut self) {
+        for stripe in Stripes(self.stripes) {
+            get_sequences()[stripe].fetch_add(1, std::SeqCst);
+        }
+    }
+}
+
+/// Iterates over the indices of the bits set, in ascending order.
+struct Stripes(u64);
+
+impl std::Iterator for Stripes {
+    type Item = usize;
+
+    fn next(&mut self) -> std::Option<Self::Item> {
+        if self.0 == 0 {
+            return std::None;
+        }
+
+        let stripe = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+
+        std::Some(stripe)
+    }
+}
+
+/// Returns the stripe of the cell at `address` as a set with a single bit.
+fn stripe_of(address: usize) -> u64 {
+    const FIBONACCI: u64 = 0x9e37_79b9_7f4a_7c15;
+    const SHIFT: u32 = u64::BITS - STRIPES.trailing_zeros();
+
+    1 << ((address as u64).wrapping_mul(FIBONACCI) >> SHIFT)
+}
+
+/// Returns the wrapping sum of the sequence numbers of the stripes, unless
+/// any of them is odd, i.e. being published to.
+///
+/// Sequence numbers only ever grow, so the sum is unchanged, if and only if
+/// every single one of them is.
+fn sum_sequences(stripes: u64) -> std::Option<usize> {
+    let mut sum = 0usize;
+
+    for stripe in Stripes(stripes) {
+        let sequence = get_sequences()[stripe].load(std::SeqCst);
+
+        if sequence & 1 != 0 {
+            return std::None;
+        }
+
+        sum = sum.wrapping_add(sequence);
+    }
+
+    std::Some(sum)
+}
+
+/// Makes the sequence number odd, once it's even.
+fn lock_sequence(sequence: &std::AtomicUsize) {
+    let mut before = sequence.load(std::SeqCst);
+
+    loop {
+        if before & 1 == 0 {
+            let result = sequence.compare_exchange_weak(
+                before,
+                before.wrapping_add(1),
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                std::Ok(_) => {
+                    return;
+                }
+                std::Err(before_current) => {
+                    before = before_current;
+                    continue;
+                }
+            }
+        }
+
+        std::yield_now();
+        before = sequence.load(std::SeqCst);
+    }
+}
+
+#[cfg(loom)]
+fn new_sequences() -> Sequences {
+    [(); STRIPES].map(|()| crate::Align128(std::AtomicUsize::new(0)))
+}
+
+fn get_sequences() -> &'static Sequences {
+    &SEQUENCES
+}
+
+macro_rules! impl_cells {
+    ($(($T:ident, $index:tt)),+) => {
+        impl<'a, $($T),+> Cells<'a> for ($(&'a crate::AtomicCell<$T>,)+) {}
+
+        impl<'a, $($T),+> UnlockedCells<'a>
+            for ($(&'a crate::AtomicCell<$T>,)+)
+        {
+            type Guards = ($(crate::Guard<'a, $T>,)+);
+            type Values = ($($T,)+);
+            type Previous = ($(std::Option<crate::ArcHandle<$T>>,)+);
+
+            fn load_guards(self) -> Self::Guards {
+                ($(self.$index.load_guard(),)+)
+            }
+
+            fn is_current(self, guards: &Self::Guards) -> bool {
+                true $(&& guards.$index.version() == self.$index.version())+
+            }
+
+            fn set_all(self, values: Self::Values) -> Self::Previous {
+                ($(self.$index.set_keeping_previous(values.$index),)+)
+            }
+
+            fn stripes(self) -> u64 {
+                0 $(| stripe_of(self.$index as *const _ as usize))+
+            }
+        }
+    };
+}
+
+impl_cells!((A, 0));
+impl_cells!((A, 0), (B, 1));
+impl_cells!((A, 0), (B, 1), (C, 2));
+impl_cells!((A, 0), (B, 1), (C, 2), (D, 3));
+impl_cells!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4));
+impl_cells!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5));
+impl_cells!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6));
+impl_cells!(
+    (A, 0),
+    (B, 1),
+    (C, 2),
+    (D, 3),
+    (E, 4),
+    (F, 5),
+    (G, 6),
+    (H, 7)
+);
@@ -15,6 +15,14 @@ impl<T> Cell<T> {
         }
     }
 
+    /// Borrows the value, which hasn't been taken by a consumer, yet.
+    #[cfg(feature = "serde")]
+    pub(crate) fn pending(&self) -> std::Option<&T> {
+        // SAFETY: Consumers and producers borrow the cell mutably, i.e. none
+        //   of them is alive while the cell is borrowed.
+        unsafe { self.shared_ptr.load(std::SeqCst).as_ref() }
+    }
+
     pub fn split(&mut self) -> (Consumer<'_, T>, Producer<'_, T>) {
         let origin = &*self;
 
@@ -5,39 +5,76 @@ pub use ::loom::alloc::dealloc;
 #[cfg(loom)]
 pub use ::loom::alloc::Layout;
 #[cfg(loom)]
+pub use ::loom::cell::UnsafeCell;
+#[cfg(loom)]
+pub use ::loom::lazy_static;
+#[cfg(loom)]
+pub use ::loom::sync::atomic::fence;
+#[cfg(loom)]
+pub use ::loom::sync::atomic::AtomicBool;
+#[cfg(loom)]
 pub use ::loom::sync::atomic::AtomicIsize;
 #[cfg(loom)]
 pub use ::loom::sync::atomic::AtomicPtr;
 #[cfg(loom)]
 pub use ::loom::sync::atomic::AtomicU32;
 #[cfg(loom)]
+pub use ::loom::sync::atomic::AtomicU64;
+#[cfg(loom)]
 pub use ::loom::sync::atomic::AtomicU8;
 #[cfg(loom)]
 pub use ::loom::sync::atomic::AtomicUsize;
+#[cfg(loom)]
+pub use ::loom::sync::Notify;
+#[cfg(loom)]
+pub use ::loom::thread::yield_now;
+#[cfg(loom)]
+pub use ::loom::thread_local;
 #[cfg(not(loom))]
 pub use ::std::alloc::alloc;
 #[cfg(not(loom))]
 pub use ::std::alloc::dealloc;
+pub use ::std::alloc::handle_alloc_error;
 #[cfg(not(loom))]
 pub use ::std::alloc::Layout;
+pub use ::std::any::type_name;
 pub use ::std::assert;
 pub use ::std::borrow::Borrow;
 pub use ::std::borrow::BorrowMut;
 pub use ::std::boxed::Box;
+pub use ::std::cell::Cell;
+#[cfg(not(loom))]
 pub use ::std::cell::UnsafeCell;
+pub use ::std::clone::Clone;
+pub use ::std::cmp::Eq;
+pub use ::std::cmp::PartialEq;
 pub use ::std::convert::AsMut;
 pub use ::std::convert::AsRef;
+pub use ::std::convert::From;
 pub use ::std::convert::Into;
 pub use ::std::convert::TryInto;
 pub use ::std::debug_assert;
 pub use ::std::default::Default;
+pub use ::std::error::Error;
+pub use ::std::fmt;
+pub use ::std::future::Future;
 pub use ::std::hint::unreachable_unchecked;
+pub use ::std::iter::FromIterator;
+pub use ::std::iter::IntoIterator;
+pub use ::std::iter::Iterator;
+// loom's `lazy_static!` and `thread_local!` refer to `std::marker`, which
+// resolves to this module, wherever they're used.
+#[cfg(loom)]
+pub use ::std::marker;
 pub use ::std::marker::Copy;
 pub use ::std::marker::PhantomData;
 pub use ::std::marker::Send;
 pub use ::std::marker::Sized;
 pub use ::std::marker::Sync;
+pub use ::std::mem::align_of;
 pub use ::std::mem::drop;
+pub use ::std::mem::forget;
+pub use ::std::mem::needs_drop;
 pub use ::std::mem::size_of;
 pub use ::std::mem::ManuallyDrop;
 pub use ::std::mem::MaybeUninit;
@@ -45,17 +82,24 @@ pub use ::std::num::NonZeroU8;
 pub use ::std::ops::Deref;
 pub use ::std::ops::DerefMut;
 pub use ::std::ops::Drop;
+pub use ::std::ops::FnMut;
 pub use ::std::ops::FnOnce;
 pub use ::std::option::Option;
 pub use ::std::option::Option::None;
 pub use ::std::option::Option::Some;
 pub use ::std::panic;
+pub use ::std::pin::Pin;
 pub use ::std::ptr;
 pub use ::std::ptr::null_mut;
 pub use ::std::ptr::NonNull;
 pub use ::std::result::Result;
 pub use ::std::result::Result::Err;
 pub use ::std::result::Result::Ok;
+pub use ::std::string::String;
+#[cfg(not(loom))]
+pub use ::std::sync::atomic::fence;
+#[cfg(not(loom))]
+pub use ::std::sync::atomic::AtomicBool;
 #[cfg(not(loom))]
 pub use ::std::sync::atomic::AtomicIsize;
 #[cfg(not(loom))]
@@ -63,12 +107,78 @@ pub use ::std::sync::atomic::AtomicPtr;
 #[cfg(not(loom))]
 pub use ::std::sync::atomic::AtomicU32;
 #[cfg(not(loom))]
+pub use ::std::sync::atomic::AtomicU64;
+#[cfg(not(loom))]
 pub use ::std::sync::atomic::AtomicU8;
 #[cfg(not(loom))]
 pub use ::std::sync::atomic::AtomicUsize;
 pub use ::std::sync::atomic::Ordering;
 pub use ::std::sync::atomic::Ordering::AcqRel;
+pub use ::std::sync::atomic::Ordering::Acquire;
 pub use ::std::sync::atomic::Ordering::Relaxed;
+pub use ::std::sync::atomic::Ordering::Release;
 pub use ::std::sync::atomic::Ordering::SeqCst;
 pub use ::std::sync::Mutex;
+pub use ::std::task::Context;
+pub use ::std::task::Poll;
+pub use ::std::task::Waker;
+#[cfg(not(loom))]
+pub use ::std::thread::current;
+#[cfg(not(loom))]
+pub use ::std::thread::park;
+#[cfg(not(loom))]
+pub use ::std::thread::park_timeout;
+#[cfg(not(loom))]
+pub use ::std::thread::yield_now;
+#[cfg(not(loom))]
+pub use ::std::thread::Thread;
+#[cfg(not(loom))]
+pub use ::std::thread_local;
+pub use ::std::time::Duration;
+#[cfg(not(loom))]
+pub use ::std::time::Instant;
 pub use ::std::todo;
+pub use ::std::unreachable;
+pub use ::std::vec::Vec;
+
+/// Stores the initial value of an atomic variable again.
+///
+/// loom doesn't treat the initial value of an atomic variable as a `SeqCst`
+/// store, i.e. `SeqCst` loads could still return it after later stores. Without
+/// loom, this does nothing.
+pub fn seqcst_init<A>(atomic: &A)
+where
+    A: SeqCstInit,
+{
+    #[cfg(loom)]
+    atomic.store_initial();
+    #[cfg(not(loom))]
+    let _ = atomic;
+}
+
+pub trait SeqCstInit {
+    #[cfg(loom)]
+    fn store_initial(&self);
+}
+
+macro_rules! impl_seqcst_init {
+    ($($atomic:ty),*) => {
+        $(
+            impl SeqCstInit for $atomic {
+                #[cfg(loom)]
+                fn store_initial(&self) {
+                    self.store(self.load(Relaxed), SeqCst);
+                }
+            }
+        )*
+    };
+}
+
+impl_seqcst_init!(AtomicBool, AtomicUsize);
+
+impl<T> SeqCstInit for AtomicPtr<T> {
+    #[cfg(loom)]
+    fn store_initial(&self) {
+        self.store(self.load(Relaxed), SeqCst);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::std;
+
+// The cell is an implementation of the Left-Right technique. The value is kept
+// in two instances. Readers announce their presence on one of two read
+// indicators, read the instance `left_right` points to and depart from the read
+// indicator again. That's 2 unconditional `fetch_add`s and 2 loads, i.e. the
+// number of steps of a read is bounded, no matter how many writers are active.
+//
+// Writers are serialized. A writer updates the instance no reader is directed
+// to and redirects new readers to it. Readers, which are still reading the
+// old instance, have announced themselves on the read indicator
+// `version_index` pointed to at that time. The writer toggles `version_index`
+// and waits for both read indicators to drain, one after the other. After
+// that no reader can be reading the old instance and it is updated, too.
+//
+// Waiting for the read indicator of the next version before toggling
+// `version_index` is necessary, because readers, which loaded the old value
+// of `version_index` a long time ago, may only now be arriving.
+pub struct WaitFreeCell<T>
+where
+    T: std::Copy,
+{
+    instances: [crate::Align128<std::UnsafeCell<T>>; 2],
+    left_right: crate::Align128<std::AtomicUsize>,
+    version_index: crate::Align128<std::AtomicUsize>,
+    read_indicators: [ReadIndicator; 2],
+    writer: crate::Align128<std::AtomicBool>,
+}
+
+unsafe impl<T> std::Sync for WaitFreeCell<T> where
+    T: std::Copy + std::Send + std::Sync
+{
+}
+
+impl<T> WaitFreeCell<T>
+where
+    T: std::Copy,
+{
+    pub fn new(value: T) -> Self {
+        let cell = Self {
+            instances: [
+                crate::Align128(std::UnsafeCell::new(value)),
+                crate::Align128(std::UnsafeCell::new(value)),
+            ],
+            left_right: crate::Align128(std::AtomicUsize::new(0)),
+            version_index: crate::Align128(std::AtomicUsize::new(0)),
+            read_indicators: [ReadIndicator::new(), ReadIndicator::new()],
+            writer: crate::Align128(std::AtomicBool::new(false)),
+        };
+
+        std::seqcst_init(&cell.left_right.0);
+        std::seqcst_init(&cell.version_index.0);
+
+        for read_indicator in &cell.read_indicators {
+            std::seqcst_init(&read_indicator.ingress.0);
+            std::seqcst_init(&read_indicator.egress.0);
+        }
+
+        cell
+    }
+
+    /// Returns a copy of the current value.
+    ///
+    /// This method is wait-free, i.e. it finishes in a bounded number of
+    /// steps, regardless of concurrent calls to [`set`][set].
+    ///
+    /// [set]: struct.WaitFreeCell.html#method.set
+    pub fn get(&self) -> T {
+        let version_index = self.version_index.load(std::SeqCst);
+        let read_indicator = &self.read_indicators[version_index];
+
+        read_indicator.arrive();
+
+        let left_right = self.left_right.load(std::SeqCst);
+
+        // SAFETY: Writers don't modify the instance `left_right` points to,
+        //   until all readers, which arrived at the read indicator, departed.
+        let value = unsafe { Self::read(&self.instances[left_right]) };
+
+        read_indicator.depart();
+
+        value
+    }
+
+    /// Stores `value` in the cell.
+    ///
+    /// Writers block each other and wait for readers of the previous value to
+    /// finish reading it.
+    pub fn set(&self, value: T) {
+        while self
+            .writer
+            .compare_exchange_weak(false, true, std::SeqCst, std::SeqCst)
+            .is_err()
+        {
+            std::yield_now();
+        }
+
+        let left_right = self.left_right.load(std::SeqCst);
+
+        // SAFETY: Readers are never directed to the other instance while the
+        //   writer lock is held.
+        unsafe { Self::write(&self.instances[left_right ^ 1], value) };
+
+        self.left_right.store(left_right ^ 1, std::SeqCst);
+
+        let version_index = self.version_index.load(std::SeqCst);
+
+        self.read_indicators[version_index ^ 1].wait_until_empty();
+        self.version_index.store(version_index ^ 1, std::SeqCst);
+        self.read_indicators[version_index].wait_until_empty();
+
+        // SAFETY: All readers of this instance have departed and new readers
+        //   are directed to the other instance.
+        unsafe { Self::write(&self.instances[left_right], value) };
+
+        self.writer.store(false, std::SeqCst);
+    }
+
+    unsafe fn read(instance: &std::UnsafeCell<T>) -> T {
+        #[cfg(loom)]
+        {
+            instance.with(|ptr| *ptr)
+        }
+        #[cfg(not(loom))]
+        {
+            *instance.get()
+        }
+    }
+
+    unsafe fn write(instance: &std::UnsafeCell<T>, value: T) {
+        #[cfg(loom)]
+        instance.with_mut(|ptr| *ptr = value);
+        #[cfg(not(loom))]
+        {
+            *instance.get() = value;
+        }
+    }
+}
+
+struct ReadIndicator {
+    ingress: crate::Align128<std::AtomicUsize>,
+    egress: crate::Align128<std::AtomicUsize>,
+}
+
+impl ReadIndicator {
+    fn new() -> Self {
+        Self {
+            ingress: crate::Align128(std::AtomicUsize::new(0)),
+            egress: crate::Align128(std::AtomicUsize::new(0)),
+        }
+    }
+
+    fn arrive(&self) {
+        self.ingress.fetch_add(1, std::SeqCst);
+    }
+
+    fn depart(&self) {
+        self.egress.fetch_add(1, std::SeqCst);
+    }
+
+    fn wait_until_empty(&self) {
+        // Both counters wrap around, which doesn't matter, because only
+        // equality is of interest. Loading `egress` first ensures, that the
+        // indicator only looks empty, if every reader, which arrived before
+        // `ingress` has been loaded, departed.
+        loop {
+            let egress = self.egress.load(std::SeqCst);
+
+            if egress == self.ingress.load(std::SeqCst) {
+                return;
+            }
+
+            std::yield_now();
+        }
+    }
+}
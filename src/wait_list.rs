@@ -0,0 +1,380 @@
+use crate::std;
+
+// Threads waiting for a change push a node onto a lock-free stack and park
+// until the node has been notified. Notifiers take the whole stack at once by
+// swapping in a null pointer, which rules out the ABA problem of popping
+// single nodes. If the stack is empty, notifying costs a single atomic load.
+//
+// A waiter pushes its node before checking its condition a second time and
+// notifiers check the stack after changing the state the condition depends
+// on. All of these accesses are sequentially consistent, i.e. either the
+// waiter observes the change or the notifier observes the node.
+//
+// Nodes are shared by the waiter and the stack and freed by whoever releases
+// their reference last. A waiter, which returns without being notified, tries
+// to unlink its node, if it's still at the top of the stack. Otherwise, the
+// node is abandoned and released by the next notification.
+//
+// Asynchronous waiters register a node holding a `Waker` instead of parking.
+// Nodes never change after they have been pushed, so a waiter polled with a
+// different waker registers a new node and abandons the previous one.
+//
+// Without notifications, abandoned nodes would pile up. Nodes below the top
+// can't be unlinked safely, so once `PRUNE_THRESHOLD` nodes have been
+// abandoned, the next push notifies all nodes on the stack. Waiters tolerate
+// spurious notifications already, i.e. the ones still waiting push a new node.
+pub struct WaitList {
+    head: std::AtomicPtr<Waiter>,
+    abandoned: std::AtomicUsize,
+}
+
+/// The number of abandoned nodes, after which the stack is pruned.
+const PRUNE_THRESHOLD: usize = 64;
+
+struct Waiter {
+    refs: std::AtomicUsize,
+    next: *mut Waiter,
+    notified: std::AtomicBool,
+    wake: Wake,
+}
+
+enum Wake {
+    #[cfg(loom)]
+    Notify(std::Notify),
+    #[cfg(not(loom))]
+    Thread(std::Thread),
+    Waker(std::Waker),
+}
+
+/// A node registered by [`WaitList::register`][register], which is removed
+/// from the list when dropped.
+///
+/// [register]: struct.WaitList.html#method.register
+pub struct Registration<'a> {
+    list: &'a WaitList,
+    waiter: *mut Waiter,
+}
+
+// SAFETY: The registration only grants shared access to the node, whose
+//   mutable state is atomic. Wakers are `Send` and `Sync`.
+unsafe impl<'a> std::Send for Registration<'a> {}
+unsafe impl<'a> std::Sync for Registration<'a> {}
+
+impl WaitList {
+    pub fn new() -> Self {
+        Self {
+            head: std::AtomicPtr::new(std::null_mut()),
+            abandoned: std::AtomicUsize::new(0),
+        }
+    }
+
+    /// Blocks the current thread until `condition` returns `true`.
+    ///
+    /// `condition` is checked again after each call to `notify_all`.
+    pub fn wait_until<F>(&self, mut condition: F)
+    where
+        F: std::FnMut() -> bool,
+    {
+        while !condition() {
+            let waiter = self.push(Waiter::new(Wake::current()));
+
+            // SAFETY: The waiter holds a reference to the node.
+            unsafe {
+                if !condition() {
+                    (*waiter).wait();
+                } else {
+                    self.abandon(waiter);
+                }
+
+                Waiter::release(waiter);
+            }
+        }
+    }
+
+    /// Like `wait_until`, but returns `false`, if `condition` is still `false`
+    /// after `timeout` has elapsed.
+    #[cfg(not(loom))]
+    pub fn wait_until_timeout<F>(
+        &self,
+        mut condition: F,
+        timeout: std::Duration,
+    ) -> bool
+    where
+        F: std::FnMut() -> bool,
+    {
+        let deadline = std::Instant::now() + timeout;
+
+        while !condition() {
+            let waiter = self.push(Waiter::new(Wake::current()));
+
+            // SAFETY: The waiter holds a reference to the node.
+            unsafe {
+                let timed_out = if condition() {
+                    self.abandon(waiter);
+                    false
+                } else if (*waiter).wait_until_deadline(deadline) {
+                    false
+                } else {
+                    self.abandon(waiter);
+                    true
+                };
+
+                Waiter::release(waiter);
+
+                if timed_out {
+                    return condition();
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Registers `waker` to be woken by the next call to `notify_all`.
+    pub fn register(&self, waker: std::Waker) -> Registration<'_> {
+        Registration {
+            list: self,
+            waiter: self.push(Waiter::new(Wake::Waker(waker))),
+        }
+    }
+
+    /// Wakes up all threads waiting in `wait_until` and all registered
+    /// wakers.
+    pub fn notify_all(&self) {
+        if self.load_head().is_null() {
+            return;
+        }
+
+        let mut waiter = self.head.swap(std::null_mut(), std::SeqCst);
+
+        while !waiter.is_null() {
+            // SAFETY: The stack's reference to the node has been taken over.
+            unsafe {
+                let next = (*waiter).next;
+
+                (*waiter).notify();
+                Waiter::release(waiter);
+
+                waiter = next;
+            }
+        }
+    }
+
+    /// Loads the top of the stack after changing the state waiters depend on.
+    fn load_head(&self) -> *mut Waiter {
+        // loom doesn't model the single total order of `SeqCst` operations
+        // on different atomic variables, i.e. the load may miss a node pushed
+        // before the waiter checked its condition. A read-modify-write
+        // operation always reads the latest node. If it succeeds, it stores
+        // the null pointer it has read.
+        #[cfg(loom)]
+        {
+            match self.head.compare_exchange(
+                std::null_mut(),
+                std::null_mut(),
+                std::SeqCst,
+                std::SeqCst,
+            ) {
+                std::Ok(head) | std::Err(head) => head,
+            }
+        }
+        #[cfg(not(loom))]
+        {
+            self.head.load(std::SeqCst)
+        }
+    }
+
+    /// Pushes a new node onto the stack and returns it with one reference held
+    /// by the caller and one held by the stack.
+    fn push(&self, waiter: Waiter) -> *mut Waiter {
+        if self.abandoned.load(std::SeqCst) >= PRUNE_THRESHOLD
+            && self.abandoned.swap(0, std::SeqCst) >= PRUNE_THRESHOLD
+        {
+            self.notify_all();
+        }
+
+        let waiter = std::Box::into_raw(std::Box::new(waiter));
+        let mut head = self.head.load(std::SeqCst);
+
+        loop {
+            // SAFETY: The node hasn't been published, yet.
+            unsafe { (*waiter).next = head };
+
+            let result = self.head.compare_exchange(
+                head,
+                waiter,
+                std::SeqCst,
+                std::SeqCst,
+            );
+
+            match result {
+                std::Ok(_) => {
+                    return waiter;
+                }
+                std::Err(head_current) => {
+                    head = head_current;
+                }
+            }
+        }
+    }
+
+    /// Removes `waiter` from the stack and releases the stack's reference, if
+    /// `waiter` is at the top of the stack. Otherwise, `waiter` is left to be
+    /// released by the next notification.
+    ///
+    /// Nodes are pushed only once and never change their successor, i.e.
+    /// `waiter` is still part of the stack, if it's at the top, and its
+    /// successor is the next node of the stack.
+    ///
+    /// # Safety
+    ///
+    /// The caller must hold a reference to `waiter`.
+    unsafe fn abandon(&self, waiter: *mut Waiter) {
+        let result = self.head.compare_exchange(
+            waiter,
+            (*waiter).next,
+            std::SeqCst,
+            std::SeqCst,
+        );
+
+        match result {
+            std::Ok(_) => Waiter::release(waiter),
+            // Notified nodes have been taken off the stack already. Counting
+            // a node, which is being taken concurrently, only prunes earlier.
+            std::Err(_) => {
+                if !(*waiter).notified.load(std::SeqCst) {
+                    self.abandoned.fetch_add(1, std::SeqCst);
+                }
+            }
+        }
+    }
+}
+
+impl std::Drop for WaitList {
+    fn drop(&mut self) {
+        let mut waiter = self.head.swap(std::null_mut(), std::SeqCst);
+
+        while !waiter.is_null() {
+            // SAFETY: Waiters, which left their node on the stack, have
+            //   returned already.
+            unsafe {
+                let next = (*waiter).next;
+                Waiter::release(waiter);
+                waiter = next;
+            }
+        }
+    }
+}
+
+impl<'a> Registration<'a> {
+    /// Returns `true`, if `notify_all` has been called after registering.
+    pub fn is_notified(&self) -> bool {
+        // SAFETY: The registration holds a reference to the node.
+        unsafe { (*self.waiter).notified.load(std::SeqCst) }
+    }
+
+    /// Returns `true`, if waking the registered waker would wake the same
+    /// task as `waker`.
+    pub fn will_wake(&self, waker: &std::Waker) -> bool {
+        // SAFETY: The registration holds a reference to the node.
+        match unsafe { &(*self.waiter).wake } {
+            Wake::Waker(registered) => registered.will_wake(waker),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> std::Drop for Registration<'a> {
+    fn drop(&mut self) {
+        // SAFETY: The registration holds a reference to the node.
+        unsafe {
+            self.list.abandon(self.waiter);
+            Waiter::release(self.waiter);
+        }
+    }
+}
+
+impl Waiter {
+    fn new(wake: Wake) -> Self {
+        Self {
+            refs: std::AtomicUsize::new(2),
+            next: std::null_mut(),
+            notified: std::AtomicBool::new(false),
+            wake,
+        }
+    }
+
+    fn notify(&self) {
+        self.notified.store(true, std::SeqCst);
+
+        match &self.wake {
+            #[cfg(loom)]
+            Wake::Notify(notify) => notify.notify(),
+            #[cfg(not(loom))]
+            Wake::Thread(thread) => thread.unpark(),
+            Wake::Waker(waker) => waker.wake_by_ref(),
+        }
+    }
+
+    /// Parks the current thread until the node has been notified.
+    fn wait(&self) {
+        while !self.notified.load(std::SeqCst) {
+            self.wake.park();
+        }
+    }
+
+    /// Like `wait`, but returns `false`, if the deadline has passed before
+    /// the node has been notified.
+    #[cfg(not(loom))]
+    fn wait_until_deadline(&self, deadline: std::Instant) -> bool {
+        while !self.notified.load(std::SeqCst) {
+            let now = std::Instant::now();
+
+            if now >= deadline {
+                return false;
+            }
+
+            std::park_timeout(deadline - now);
+        }
+
+        true
+    }
+
+    /// Releases a reference to `waiter` and frees it, if it has been the last
+    /// one.
+    ///
+    /// # Safety
+    ///
+    /// The caller must hold a reference to `waiter`, which must not be used
+    /// afterwards.
+    unsafe fn release(waiter: *mut Waiter) {
+        if (*waiter).refs.fetch_sub(1, std::SeqCst) == 1 {
+            std::drop(std::Box::from_raw(waiter));
+        }
+    }
+}
+
+impl Wake {
+    /// Creates a way to wake up the current thread.
+    fn current() -> Self {
+        #[cfg(loom)]
+        {
+            Wake::Notify(std::Notify::new())
+        }
+        #[cfg(not(loom))]
+        {
+            Wake::Thread(std::current())
+        }
+    }
+
+    /// Blocks the current thread until woken up. May return spuriously.
+    fn park(&self) {
+        match self {
+            #[cfg(loom)]
+            Wake::Notify(notify) => notify.wait(),
+            #[cfg(not(loom))]
+            Wake::Thread(_) => std::park(),
+            Wake::Waker(_) => std::unreachable!(),
+        }
+    }
+}
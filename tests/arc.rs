@@ -0,0 +1,22 @@
+#![cfg(not(loom))]
+
+use ::impatience::Arc;
+
+#[test]
+fn new_with_panic() {
+    let result = ::std::panic::catch_unwind(|| unsafe {
+        Arc::<[u64; 64]>::new_with(|_| panic!("initialization failed"))
+    });
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn slice_weak() {
+    let arc: Arc<[String]> = vec![String::from("a"), String::from("b")].into();
+    let weak = Arc::downgrade(&arc);
+
+    drop(arc);
+
+    assert!(weak.upgrade().is_none());
+}
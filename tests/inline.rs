@@ -0,0 +1,129 @@
+#![cfg(not(loom))]
+
+use ::impatience::AtomicCell;
+use ::std::cell::Cell;
+use ::std::mem::MaybeUninit;
+use ::std::num::NonZeroU32;
+use ::std::sync::atomic::AtomicU32;
+use ::std::sync::atomic::Ordering;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Padded {
+    a: u8,
+    b: u16,
+}
+
+#[test]
+fn padded() {
+    assert!(!AtomicCell::<Padded>::is_inline());
+
+    let cell = AtomicCell::new(Padded { a: 1, b: 2 });
+
+    assert_eq!(cell.swap(Padded { a: 3, b: 4 }), Padded { a: 1, b: 2 });
+    assert_eq!(
+        cell.compare_exchange(Padded { a: 3, b: 4 }, Padded { a: 5, b: 6 }),
+        Ok(Padded { a: 3, b: 4 }),
+    );
+    assert_eq!(cell.get(), Padded { a: 5, b: 6 });
+}
+
+#[test]
+fn uninitialized_payload() {
+    assert!(!AtomicCell::<Option<u8>>::is_inline());
+
+    let cell = AtomicCell::new(None::<u8>);
+
+    cell.set(Some(1));
+
+    assert_eq!(cell.swap(None), Some(1));
+    assert_eq!(cell.get(), None);
+}
+
+#[test]
+fn niche() {
+    assert!(!AtomicCell::<Option<NonZeroU32>>::is_inline());
+
+    let cell = AtomicCell::new(NonZeroU32::new(1));
+
+    assert_eq!(cell.swap(None), NonZeroU32::new(1));
+    assert_eq!(cell.compare_exchange(None, NonZeroU32::new(2)), Ok(None),);
+    assert_eq!(*cell.load_guard(), NonZeroU32::new(2));
+}
+
+#[test]
+fn primitives() {
+    assert!(AtomicCell::<bool>::is_inline());
+    assert!(AtomicCell::<char>::is_inline());
+    assert!(AtomicCell::<f32>::is_inline());
+    assert!(AtomicCell::<i8>::is_inline());
+    assert!(AtomicCell::<u16>::is_inline());
+    assert!(AtomicCell::<u32>::is_inline());
+}
+
+#[test]
+fn not_inline() {
+    #[repr(align(4))]
+    struct Aligned(u8);
+
+    assert!(!AtomicCell::<Aligned>::is_inline());
+    assert!(!AtomicCell::<MaybeUninit<u32>>::is_inline());
+    assert!(!AtomicCell::<MaybeUninit<[u8; 4]>>::is_inline());
+    assert!(!AtomicCell::<AtomicU32>::is_inline());
+    assert!(!AtomicCell::<Cell<u32>>::is_inline());
+}
+
+#[test]
+fn interior_mutability() {
+    let cell = AtomicCell::new(AtomicU32::new(0));
+
+    cell.load_guard().fetch_add(1, Ordering::SeqCst);
+
+    assert_eq!(cell.load_guard().load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn wide() {
+    assert!(!AtomicCell::<u64>::is_inline());
+    assert!(AtomicCell::<u64>::is_lock_free());
+
+    let cell = AtomicCell::new(1u64);
+
+    assert_eq!(cell.swap(2), 1);
+    assert_eq!(cell.compare_exchange(2, 3), Ok(2));
+    assert_eq!(cell.get(), 3);
+}
+
+#[test]
+fn clone() {
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    struct Id(u16);
+
+    assert!(!AtomicCell::<Id>::is_inline());
+
+    let cell = AtomicCell::new(Id(1));
+    let guard = cell.load_guard();
+
+    assert_eq!(cell.swap(Id(2)), Id(1));
+    assert_eq!(*guard, Id(1));
+    assert_eq!(cell.get_cloned(), Id(2));
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "takes too long without optimizations")]
+fn version_wraparound() {
+    let mut cell = AtomicCell::new(0u32);
+    let (version, _) = cell.get_versioned();
+
+    for _ in 0..u32::MAX {
+        *cell.get_mut() += 1;
+    }
+
+    assert_ne!(cell.get_versioned().0, version);
+
+    *cell.get_mut() += 1;
+
+    // The version of 4 byte values wraps around after 2^32 writes.
+    assert_eq!(cell.get_versioned(), (version, 0));
+    assert!(cell.set_if_version(version, 1).is_ok());
+}
@@ -18,3 +18,257 @@ fn arc() {
         thandle1.join().unwrap();
     });
 }
+
+struct Droppable(::loom::sync::Arc<::loom::sync::atomic::AtomicUsize>);
+
+impl Drop for Droppable {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, ::std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn clone() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let arc = ::impatience::Arc::new(Droppable(drops.clone()));
+
+        let thandle0 = {
+            let arc = arc.clone();
+
+            ::loom::thread::spawn(move || {
+                let raw = ::impatience::Arc::into_raw(arc);
+
+                ::std::mem::drop(unsafe {
+                    ::impatience::Arc::<Droppable>::from_raw(raw)
+                });
+            })
+        };
+
+        let clone = arc.clone();
+
+        assert!(::impatience::Arc::ptr_eq(&arc, &clone));
+        assert!(::impatience::Arc::strong_count(&arc) >= 2);
+
+        ::std::mem::drop(arc);
+        ::std::mem::drop(clone);
+
+        thandle0.join().unwrap();
+
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 1);
+    });
+}
+
+#[test]
+fn weak() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let arc = ::impatience::Arc::new(Droppable(drops.clone()));
+        let weak = ::impatience::Arc::downgrade(&arc);
+
+        let thandle0 = {
+            let drops = drops.clone();
+
+            ::loom::thread::spawn(move || {
+                if let Some(arc) = weak.upgrade() {
+                    assert_eq!(
+                        drops.load(::std::sync::atomic::Ordering::SeqCst),
+                        0
+                    );
+                    ::std::mem::drop(arc);
+                }
+            })
+        };
+
+        ::std::mem::drop(arc);
+
+        thandle0.join().unwrap();
+
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 1);
+    });
+}
+
+struct Written(::loom::cell::UnsafeCell<u64>);
+
+// SAFETY: The tests only write the value through the last `Arc`.
+unsafe impl Sync for Written {}
+
+impl Drop for Written {
+    fn drop(&mut self) {
+        self.0.with_mut(|value| unsafe { *value += 1 });
+    }
+}
+
+#[test]
+fn drop_after_write() {
+    ::loom::model(|| {
+        let arc =
+            ::impatience::Arc::new(Written(::loom::cell::UnsafeCell::new(0)));
+
+        let thandle0 = {
+            let arc = arc.clone();
+
+            ::loom::thread::spawn(move || {
+                assert_eq!(arc.0.with(|value| unsafe { *value }), 0);
+            })
+        };
+
+        ::std::mem::drop(arc);
+
+        thandle0.join().unwrap();
+    });
+}
+
+#[test]
+fn drop_weak() {
+    ::loom::model(|| {
+        let arc =
+            ::impatience::Arc::new(Written(::loom::cell::UnsafeCell::new(0)));
+        let weak = ::impatience::Arc::downgrade(&arc);
+
+        let thandle0 = ::loom::thread::spawn(move || {
+            if let Some(arc) = weak.upgrade() {
+                assert_eq!(arc.0.with(|value| unsafe { *value }), 0);
+            }
+        });
+
+        ::std::mem::drop(arc);
+
+        thandle0.join().unwrap();
+    });
+}
+
+#[test]
+fn slice() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let arc: ::impatience::Arc<[Droppable]> =
+            (0..3).map(|_| Droppable(drops.clone())).collect();
+
+        let thandle0 = {
+            let arc = arc.clone();
+
+            ::loom::thread::spawn(move || {
+                assert_eq!(arc.len(), 3);
+            })
+        };
+
+        ::std::mem::drop(arc);
+
+        thandle0.join().unwrap();
+
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 3);
+    });
+}
+
+#[test]
+fn get_mut() {
+    ::loom::model(|| {
+        let mut arc =
+            ::impatience::Arc::new(Written(::loom::cell::UnsafeCell::new(0)));
+        let weak = ::impatience::Arc::downgrade(&arc);
+
+        let thandle0 = ::loom::thread::spawn(move || {
+            if let Some(arc) = weak.upgrade() {
+                assert_eq!(arc.0.with(|value| unsafe { *value }), 0);
+            }
+        });
+
+        if let Some(written) = ::impatience::Arc::get_mut(&mut arc) {
+            written.0.with_mut(|value| unsafe { *value += 1 });
+        }
+
+        thandle0.join().unwrap();
+
+        assert!(::impatience::Arc::get_mut(&mut arc).is_some());
+    });
+}
+
+#[test]
+fn make_mut() {
+    ::loom::model(|| {
+        let mut arc =
+            ::impatience::Arc::new(Written(::loom::cell::UnsafeCell::new(0)));
+        let weak = ::impatience::Arc::downgrade(&arc);
+
+        let thandle0 = {
+            let arc = arc.clone();
+
+            ::loom::thread::spawn(move || {
+                assert_eq!(arc.0.with(|value| unsafe { *value }), 0);
+            })
+        };
+
+        let written = ::impatience::Arc::make_mut(&mut arc);
+        written.0.with_mut(|value| unsafe { *value = 2 });
+
+        thandle0.join().unwrap();
+
+        assert!(weak.upgrade().map_or(true, |previous| {
+            !::impatience::Arc::ptr_eq(&arc, &previous)
+        }));
+        assert_eq!(::impatience::Arc::weak_count(&arc), 0);
+    });
+}
+
+impl Clone for Written {
+    fn clone(&self) -> Self {
+        Written(::loom::cell::UnsafeCell::new(
+            self.0.with(|value| unsafe { *value }),
+        ))
+    }
+}
+
+#[test]
+fn into_inner() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let arc = ::impatience::Arc::new(Droppable(drops.clone()));
+
+        let thandle0 = {
+            let arc = arc.clone();
+
+            ::loom::thread::spawn(move || ::impatience::Arc::into_inner(arc))
+        };
+
+        let data = ::impatience::Arc::into_inner(arc);
+        let data_other = thandle0.join().unwrap();
+
+        assert!(data.is_some() != data_other.is_some());
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 0);
+    });
+}
+
+#[test]
+fn try_unwrap() {
+    ::loom::model(|| {
+        let arc =
+            ::impatience::Arc::new(Written(::loom::cell::UnsafeCell::new(0)));
+        let weak = ::impatience::Arc::downgrade(&arc);
+
+        let thandle0 = ::loom::thread::spawn(move || {
+            if let Some(arc) = weak.upgrade() {
+                assert_eq!(arc.0.with(|value| unsafe { *value }), 0);
+            }
+        });
+
+        let mut arc = arc;
+
+        // The thread may hold an upgraded `Arc` for a while.
+        let written = loop {
+            match ::impatience::Arc::try_unwrap(arc) {
+                Ok(written) => break written,
+                Err(arc_current) => arc = arc_current,
+            }
+
+            ::loom::thread::yield_now();
+        };
+        ::std::mem::drop(written);
+
+        thandle0.join().unwrap();
+    });
+}
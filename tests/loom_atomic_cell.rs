@@ -0,0 +1,562 @@
+#[test]
+fn swap() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u64));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.swap(1))
+        };
+
+        let thandle1 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.swap(2))
+        };
+
+        let prev0 = thandle0.join().unwrap();
+        let prev1 = thandle1.join().unwrap();
+        let last = cell.get();
+
+        let mut values = [prev0, prev1, last];
+        values.sort();
+
+        assert_eq!(values, [0, 1, 2]);
+    });
+}
+
+#[test]
+fn compare_exchange() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u64));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.compare_exchange(0, 1))
+        };
+
+        let thandle1 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.get())
+        };
+
+        let result0 = thandle0.join().unwrap();
+        let result2 = cell.compare_exchange(0, 2);
+        let read = thandle1.join().unwrap();
+
+        assert_eq!(result0, Ok(0));
+        assert_eq!(result2, Err(1));
+        assert!(read == 0 || read == 1);
+        assert_eq!(cell.get(), 1);
+    });
+}
+
+#[test]
+fn update() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u64));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.update(|value| value + 1))
+        };
+
+        let prev1 = cell.update(|value| value + 2);
+        let prev0 = thandle0.join().unwrap();
+
+        assert!((prev0, prev1) == (0, 1) || (prev0, prev1) == (2, 0));
+        assert_eq!(cell.get(), 3);
+        assert_eq!(cell.try_get(), Ok(3));
+        assert_eq!(cell.fetch_update(|_| None), Err(3));
+    });
+}
+
+#[test]
+fn load_guard() {
+    ::loom::model(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::AtomicCell::new([0u64; 4]));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.set([1; 4]))
+        };
+
+        let guard = cell.load_guard();
+        let value = *guard;
+
+        assert!(value == [0; 4] || value == [1; 4]);
+
+        thandle0.join().unwrap();
+
+        assert_eq!(*guard, value);
+        ::std::mem::drop(guard);
+        assert_eq!(cell.get(), [1; 4]);
+    });
+}
+
+struct Droppable(::loom::sync::Arc<::loom::sync::atomic::AtomicUsize>);
+
+impl Drop for Droppable {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, ::std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn drop_once() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(
+            Droppable(drops.clone()),
+        ));
+
+        let thandle0 = {
+            let cell = cell.clone();
+            let drops = drops.clone();
+
+            ::loom::thread::spawn(move || cell.set(Droppable(drops)))
+        };
+
+        let guard = cell.load_guard();
+        ::std::mem::drop(guard);
+
+        thandle0.join().unwrap();
+
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 1);
+        ::std::mem::drop(cell);
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 2);
+    });
+}
+
+#[test]
+fn swap_shared() {
+    assert!(::impatience::AtomicCell::<u32>::is_inline());
+    assert!(!::impatience::AtomicCell::<[u64; 2]>::is_inline());
+
+    ::loom::model(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::AtomicCell::new([0u64; 2]));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.swap([1; 2]))
+        };
+
+        let prev1 = cell.compare_exchange([0; 2], [2; 2]);
+        let prev0 = thandle0.join().unwrap();
+
+        assert!(prev1 == Ok([0; 2]) || prev1 == Err([1; 2]));
+        assert!(prev0 == [0; 2] || prev0 == [2; 2]);
+        assert_eq!(cell.get(), [1; 2]);
+    });
+}
+
+#[test]
+fn take_shared() {
+    ::loom::model(|| {
+        let value = vec![1u64, 2];
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(value));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.take())
+        };
+
+        let guard = cell.load_guard();
+
+        assert!(*guard == [1, 2] || guard.is_empty());
+
+        let taken = thandle0.join().unwrap();
+
+        assert_eq!(taken, [1, 2]);
+        ::std::mem::drop(guard);
+        assert!(cell.load_guard().is_empty());
+    });
+}
+
+#[test]
+fn drop_recycled() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(
+            Droppable(drops.clone()),
+        ));
+
+        let thandle0 = {
+            let cell = cell.clone();
+            let drops = drops.clone();
+
+            ::loom::thread::spawn(move || {
+                cell.set(Droppable(drops.clone()));
+                cell.set(Droppable(drops));
+            })
+        };
+
+        let guard = cell.load_guard();
+        ::std::mem::drop(guard);
+
+        // A panicking initializer returns its block to the pool without
+        // changing the cell.
+        let result =
+            ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(
+                || unsafe { cell.set_with(|_| panic!("init")) },
+            ));
+
+        thandle0.join().unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 2);
+        ::std::mem::drop(cell);
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 3);
+    });
+}
+
+#[test]
+fn load_guard_saturated() {
+    let mut builder = ::loom::model::Builder::new();
+
+    // Every registration of a guard is a branch.
+    builder.max_branches = 10_000;
+
+    builder.check(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::AtomicCell::new([0u64; 4]));
+
+        // The guards hold all registrations the read count can take, so the
+        // next reader has to increment the reference count instead.
+        let guards = (0..127).map(|_| cell.load_guard()).collect::<Vec<_>>();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || *cell.load_guard())
+        };
+
+        cell.set([1; 4]);
+
+        let value = thandle0.join().unwrap();
+
+        assert!(value == [0; 4] || value == [1; 4]);
+        assert!(guards.iter().all(|guard| **guard == [0; 4]));
+        ::std::mem::drop(guards);
+        assert_eq!(cell.get(), [1; 4]);
+    });
+}
+
+#[test]
+fn set_if_version() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u32));
+        let (version, value) = cell.get_versioned();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                cell.set(1);
+                cell.set(0);
+            })
+        };
+
+        let result = cell.set_if_version(version, 2);
+        thandle0.join().unwrap();
+
+        assert_eq!(value, 0);
+
+        match result {
+            Ok(_) => assert_eq!(cell.get(), 0),
+            Err(value) => {
+                assert_eq!(value, 2);
+                assert_eq!(cell.get(), 0);
+                assert_ne!(cell.get_versioned().0, version);
+            }
+        }
+    });
+}
+
+#[test]
+fn set_if_version_saturated() {
+    let mut builder = ::loom::model::Builder::new();
+
+    // Every registration of a guard is a branch. The writer takes too many
+    // steps to explore all interleavings with the reader.
+    builder.max_branches = 10_000;
+    builder.preemption_bound = Some(2);
+
+    builder.check(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::AtomicCell::new([0u64; 4]));
+
+        // The reader has to increment the reference count, so it may acquire
+        // a reference to the second value after it has been freed and reused
+        // by the failed `set_if_version`.
+        let guards = (0..127).map(|_| cell.load_guard()).collect::<Vec<_>>();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || *cell.load_guard())
+        };
+
+        cell.set([1; 4]);
+
+        let (version, _) = cell.get_versioned();
+
+        cell.set([2; 4]);
+        assert_eq!(cell.set_if_version(version, [3; 4]), Err([3; 4]));
+        cell.set([4; 4]);
+
+        let value = thandle0.join().unwrap();
+
+        assert!(value != [3; 4]);
+        assert!(guards.iter().all(|guard| **guard == [0; 4]));
+        ::std::mem::drop(guards);
+        assert_eq!(cell.get(), [4; 4]);
+    });
+}
+
+#[test]
+fn get_versioned_u64() {
+    assert!(!::impatience::AtomicCell::<u64>::is_inline());
+    assert!(::impatience::AtomicCell::<u64>::is_lock_free());
+
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u64));
+        let (version, _) = cell.get_versioned();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                let (version0, value0) = cell.get_versioned();
+                let (version1, value1) = cell.get_versioned();
+                (version0, value0, version1, value1)
+            })
+        };
+
+        cell.set(1);
+        cell.set(2);
+        let (version0, value0, version1, value1) = thandle0.join().unwrap();
+
+        // Every value is stored once, i.e. it determines the version.
+        assert_eq!(version0 == version, value0 == 0);
+        assert_eq!(version1 == version0, value1 == value0);
+        assert!(value0 <= value1);
+        assert!(cell.set_if_version(version1, 3).is_err() || value1 == 2);
+    });
+}
+
+#[test]
+fn set_if_version_shared() {
+    ::loom::model(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::AtomicCell::new([0u64; 2]));
+        let (version, _) = cell.get_versioned();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.set_if_version(version, [1; 2]))
+        };
+
+        let result2 = cell.set_if_version(version, [2; 2]);
+        let result1 = thandle0.join().unwrap();
+
+        match (result1, result2) {
+            (Ok(version1), Err(value)) => {
+                assert_eq!(value, [2; 2]);
+                assert_eq!(cell.get_versioned(), (version1, [1; 2]));
+            }
+            (Err(value), Ok(version2)) => {
+                assert_eq!(value, [1; 2]);
+                assert_eq!(cell.get_versioned(), (version2, [2; 2]));
+            }
+            _ => panic!("exactly one write has to succeed"),
+        }
+
+        assert_ne!(cell.get_versioned().0, version);
+    });
+}
+
+#[test]
+fn wait_changed() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u32));
+        let (version, _) = cell.get_versioned();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.wait_changed(version))
+        };
+
+        cell.set(1);
+
+        let changed = thandle0.join().unwrap();
+
+        assert_ne!(changed, version);
+        assert_eq!(cell.get_versioned(), (changed, 1));
+    });
+}
+
+#[test]
+fn wait_changed_shared() {
+    ::loom::model(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::AtomicCell::new([0u64; 2]));
+        let (version, _) = cell.get_versioned();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || cell.wait_changed(version))
+        };
+
+        cell.set([1; 2]);
+
+        let changed = thandle0.join().unwrap();
+
+        assert_ne!(changed, version);
+        assert_eq!(cell.get_versioned(), (changed, [1; 2]));
+    });
+}
+
+#[test]
+fn changed() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u32));
+        let (version, _) = cell.get_versioned();
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                ::loom::future::block_on(cell.changed_since(version))
+            })
+        };
+
+        cell.set(1);
+
+        let changed = thandle0.join().unwrap();
+
+        assert_ne!(changed, version);
+        assert_eq!(cell.get_versioned(), (changed, 1));
+    });
+}
+
+#[test]
+fn snapshot() {
+    ::loom::model(|| {
+        let cells = ::loom::sync::Arc::new((
+            ::impatience::AtomicCell::new(0u32),
+            ::impatience::AtomicCell::new(0u32),
+        ));
+
+        let thandle0 = {
+            let cells = cells.clone();
+
+            ::loom::thread::spawn(move || {
+                let (a, b) = ::impatience::snapshot((&cells.0, &cells.1));
+
+                (*a, *b)
+            })
+        };
+
+        ::impatience::publish_all((&cells.0, &cells.1), (1, 1));
+
+        let (a, b) = thandle0.join().unwrap();
+
+        assert_eq!(a, b);
+    });
+}
+
+/// Takes a snapshot of another cell, when dropped.
+struct Snapshotting(::loom::sync::Arc<::impatience::AtomicCell<u32>>);
+
+impl Drop for Snapshotting {
+    fn drop(&mut self) {
+        ::impatience::snapshot((&*self.0,));
+    }
+}
+
+#[test]
+fn publish_all_drop() {
+    ::loom::model(|| {
+        let b = ::loom::sync::Arc::new(::impatience::AtomicCell::new(0u32));
+        let a = ::impatience::AtomicCell::new(Snapshotting(b.clone()));
+
+        // The previous value of `a` is dropped, after `b` has been released.
+        ::impatience::publish_all((&a, &*b), (Snapshotting(b.clone()), 1));
+
+        assert_eq!(b.get(), 1);
+    });
+}
+
+#[test]
+fn into_inner() {
+    ::loom::model(|| {
+        let drops =
+            ::loom::sync::Arc::new(::loom::sync::atomic::AtomicUsize::new(0));
+        let cell = ::impatience::AtomicCell::new(Droppable(drops.clone()));
+
+        let thandle0 = {
+            let drops = drops.clone();
+
+            ::loom::thread::spawn(move || {
+                cell.set(Droppable(drops));
+                cell
+            })
+        };
+
+        let cell = thandle0.join().unwrap();
+
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 1);
+
+        let value = cell.into_inner();
+
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 1);
+        ::std::mem::drop(value);
+        assert_eq!(drops.load(::std::sync::atomic::Ordering::SeqCst), 2);
+    });
+}
+
+#[test]
+fn set_with() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(unsafe {
+            ::impatience::AtomicCell::<[u64; 4]>::new_with(|slot| {
+                slot.as_mut_ptr().write([1; 4]);
+            })
+        });
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                let value = cell.get();
+
+                assert!(value == [1; 4] || value == [2; 4]);
+            })
+        };
+
+        unsafe {
+            cell.set_with(|slot| {
+                slot.as_mut_ptr().write([2; 4]);
+            });
+        }
+
+        thandle0.join().unwrap();
+
+        assert_eq!(cell.get(), [2; 4]);
+    });
+}
@@ -0,0 +1,71 @@
+#[test]
+fn epoch_cell() {
+    ::loom::model(|| {
+        let cell = ::loom::sync::Arc::new(::impatience::EpochCell::new(0u64));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                let first = cell.get();
+                let second = cell.get();
+
+                assert!(first <= second);
+            })
+        };
+
+        cell.set(1);
+        cell.set(2);
+
+        thandle0.join().unwrap();
+
+        assert_eq!(cell.get(), 2);
+    });
+}
+
+struct Value(::loom::sync::Arc<::loom::sync::atomic::AtomicBool>);
+
+impl Value {
+    fn new() -> Self {
+        Self(::loom::sync::Arc::new(
+            ::loom::sync::atomic::AtomicBool::new(false),
+        ))
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        self.0.store(true, ::std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn load_guard() {
+    ::loom::model(|| {
+        let value = Value::new();
+        let dropped = value.0.clone();
+        let cell = ::loom::sync::Arc::new(::impatience::EpochCell::new(value));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                let guard = cell.load_guard();
+                let dropped = guard.0.clone();
+
+                ::loom::thread::yield_now();
+
+                assert!(!dropped.load(::std::sync::atomic::Ordering::SeqCst));
+            })
+        };
+
+        cell.set(Value::new());
+        cell.set(Value::new());
+
+        thandle0.join().unwrap();
+
+        ::std::mem::drop(cell);
+
+        assert!(dropped.load(::std::sync::atomic::Ordering::SeqCst));
+    });
+}
@@ -0,0 +1,24 @@
+#[test]
+fn wait_free_cell() {
+    ::loom::model(|| {
+        let cell =
+            ::loom::sync::Arc::new(::impatience::WaitFreeCell::new(0u64));
+
+        let thandle0 = {
+            let cell = cell.clone();
+
+            ::loom::thread::spawn(move || {
+                cell.set(1);
+                cell.set(2);
+            })
+        };
+
+        let first = cell.get();
+        let second = cell.get();
+
+        thandle0.join().unwrap();
+
+        assert!(first <= second);
+        assert_eq!(cell.get(), 2);
+    });
+}
@@ -0,0 +1,42 @@
+#![cfg(all(debug_assertions, not(loom)))]
+
+use ::impatience::Arc;
+use ::impatience::RawArc;
+
+#[test]
+fn from_ptr() {
+    let ptr = Arc::raw(5u32).as_ptr();
+    let arc = unsafe { Arc::from_raw(RawArc::<u32>::from_ptr(ptr)) };
+
+    unsafe { arc.init_count(1) };
+
+    assert_eq!(*arc, 5);
+}
+
+#[test]
+#[should_panic(expected = "not of an `Arc<u64>`")]
+fn from_ptr_other_type() {
+    let ptr = Arc::raw(5u32).as_ptr();
+
+    unsafe { RawArc::<u64>::from_ptr(ptr) };
+}
+
+#[test]
+#[should_panic(expected = "doesn't point to the allocation of an `Arc`")]
+fn from_ptr_foreign() {
+    let layout = ::std::alloc::Layout::from_size_align(1024, 128).unwrap();
+    let ptr = unsafe { ::std::alloc::alloc_zeroed(layout) };
+
+    unsafe { RawArc::<u32>::from_ptr(ptr as *const ()) };
+}
+
+#[test]
+fn assume_init() {
+    let mut arc = Arc::<u32>::new_uninit();
+
+    unsafe { Arc::get_mut_unchecked(&mut arc).write(7) };
+
+    let arc = unsafe { arc.assume_init() };
+
+    assert_eq!(*arc, 7);
+}
@@ -0,0 +1,109 @@
+#![cfg(not(loom))]
+
+use ::std::sync::Arc;
+use ::std::sync::Barrier;
+
+#[test]
+fn many_guards() {
+    assert!(::impatience::AtomicCell::<Arc<u32>>::is_lock_free());
+
+    let first = Arc::new(0);
+    let cell = ::impatience::AtomicCell::new(first.clone());
+
+    // More guards than the read count can register.
+    let guards = (0..200).map(|_| cell.load_guard()).collect::<Vec<_>>();
+
+    cell.set(Arc::new(1));
+
+    let guards_next = (0..200).map(|_| cell.load_guard()).collect::<Vec<_>>();
+
+    assert!(guards.iter().all(|guard| ***guard == 0));
+    assert!(guards_next.iter().all(|guard| ***guard == 1));
+    assert_eq!(Arc::strong_count(&first), 2);
+
+    drop(guards);
+
+    assert_eq!(Arc::strong_count(&first), 1);
+
+    drop(guards_next);
+
+    assert_eq!(*cell.get_cloned(), 1);
+}
+
+#[test]
+fn try_get_saturated() {
+    let cell = ::impatience::AtomicCell::new([0u64; 4]);
+    let mut guards = (0..127).map(|_| cell.load_guard()).collect::<Vec<_>>();
+
+    assert_eq!(cell.try_get(), Err(::impatience::ReaderSaturated));
+    assert_eq!(cell.get(), [0; 4]);
+
+    guards.pop();
+
+    assert_eq!(cell.try_get(), Ok([0; 4]));
+
+    guards.push(cell.load_guard());
+    cell.set([1; 4]);
+
+    // The guards are registered with the previous value.
+    assert_eq!(cell.try_get(), Ok([1; 4]));
+    assert!(guards.iter().all(|guard| **guard == [0; 4]));
+}
+
+#[test]
+fn many_readers() {
+    const READERS: usize = 192;
+
+    let cell = Arc::new(::impatience::AtomicCell::new([0u64; 4]));
+    let barrier = Arc::new(Barrier::new(READERS + 1));
+
+    let thandles = (0..READERS)
+        .map(|_| {
+            let cell = cell.clone();
+            let barrier = barrier.clone();
+
+            ::std::thread::spawn(move || {
+                let guard = cell.load_guard();
+                let value = *guard;
+
+                // All readers hold their guard at the same time.
+                barrier.wait();
+
+                for _ in 0..100 {
+                    let [a, b, c, d] = cell.get();
+
+                    assert!(a == b && b == c && c == d);
+                }
+
+                barrier.wait();
+
+                assert_eq!(*guard, value);
+            })
+        })
+        .collect::<Vec<_>>();
+
+    barrier.wait();
+
+    for i in 1..=1000 {
+        cell.set([i; 4]);
+    }
+
+    barrier.wait();
+
+    for thandle in thandles {
+        thandle.join().unwrap();
+    }
+
+    assert_eq!(cell.get(), [1000; 4]);
+}
+
+#[test]
+fn guard_debug() {
+    let cell = ::impatience::AtomicCell::new(String::from("one"));
+    let guard = cell.load_guard();
+
+    assert_eq!(
+        format!("{:?}", guard),
+        format!("Guard {{ version: {:?}, value: \"one\" }}", guard.version()),
+    );
+}
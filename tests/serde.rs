@@ -0,0 +1,49 @@
+#![cfg(all(feature = "serde", not(loom)))]
+
+use ::serde_test::Token;
+
+#[test]
+fn atomic_cell() {
+    let cell = ::impatience::AtomicCell::new(1u32);
+
+    ::serde_test::assert_ser_tokens(&cell, &[Token::U32(1)]);
+
+    cell.set(2);
+
+    ::serde_test::assert_ser_tokens(&cell, &[Token::U32(2)]);
+}
+
+#[test]
+fn atomic_cell_shared() {
+    let cell = ::impatience::AtomicCell::new(String::from("value"));
+
+    ::serde_test::assert_ser_tokens(&cell, &[Token::Str("value")]);
+
+    let deserializer: ::serde::de::value::StrDeserializer<
+        ::serde::de::value::Error,
+    > = ::serde::de::IntoDeserializer::into_deserializer("other");
+    let cell: ::impatience::AtomicCell<String> =
+        ::serde::Deserialize::deserialize(deserializer).unwrap();
+
+    assert_eq!(cell.into_inner(), "other");
+}
+
+#[test]
+fn spsc_cell() {
+    let mut cell = ::impatience::spsc::Cell::new(1u64);
+
+    ::serde_test::assert_ser_tokens(&cell, &[Token::U64(1)]);
+
+    {
+        let (mut consumer, mut producer) = cell.split();
+
+        producer.set(2);
+        assert_eq!(consumer.get(), (true, &2));
+    }
+
+    ::serde_test::assert_ser_tokens_error(
+        &cell,
+        &[],
+        "the value has been taken by a consumer",
+    );
+}
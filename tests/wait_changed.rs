@@ -0,0 +1,78 @@
+#![cfg(not(loom))]
+
+use ::std::future::Future;
+use ::std::time::Duration;
+
+#[test]
+fn wait_changed_timeout() {
+    let cell = ::std::sync::Arc::new(::impatience::AtomicCell::new(0u32));
+    let (version, _) = cell.get_versioned();
+
+    assert_eq!(
+        cell.wait_changed_timeout(version, Duration::from_millis(10)),
+        None,
+    );
+
+    let thandle0 = {
+        let cell = cell.clone();
+
+        ::std::thread::spawn(move || {
+            ::std::thread::sleep(Duration::from_millis(10));
+            cell.set(1);
+        })
+    };
+
+    let changed = cell.wait_changed_timeout(version, Duration::from_secs(60));
+
+    thandle0.join().unwrap();
+
+    let changed = changed.unwrap();
+
+    assert_ne!(changed, version);
+    assert_eq!(cell.get_versioned(), (changed, 1));
+    assert_eq!(
+        cell.wait_changed_timeout(version, Duration::from_millis(0)),
+        Some(changed),
+    );
+}
+
+#[test]
+fn changed_dropped() {
+    struct Wake;
+
+    impl ::std::task::Wake for Wake {
+        fn wake(self: ::std::sync::Arc<Self>) {}
+    }
+
+    let cell = ::impatience::AtomicCell::new(0u32);
+    let wake = ::std::sync::Arc::new(Wake);
+    let waker = ::std::task::Waker::from(wake.clone());
+    let mut cx = ::std::task::Context::from_waker(&waker);
+
+    let mut pending = Box::pin(cell.changed());
+
+    assert!(pending.as_mut().poll(&mut cx).is_pending());
+
+    // Dropping the first future leaves its node below the second one's.
+    for _ in 0..1000 {
+        let mut changed0 = Box::pin(cell.changed());
+        let mut changed1 = Box::pin(cell.changed());
+
+        assert!(changed0.as_mut().poll(&mut cx).is_pending());
+        assert!(changed1.as_mut().poll(&mut cx).is_pending());
+
+        drop(changed0);
+    }
+
+    // Each node on the wait list holds a clone of the waker.
+    assert!(::std::sync::Arc::strong_count(&wake) < 100);
+
+    cell.set(1);
+
+    let (version, _) = cell.get_versioned();
+
+    assert_eq!(
+        pending.as_mut().poll(&mut cx),
+        ::std::task::Poll::Ready(version)
+    );
+}